@@ -0,0 +1,102 @@
+// Compares keyframe lookup strategies on a per-frame baked track.
+//
+//   cargo bench --bench track_sampling
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use script_2_script::{Easing, Keyframe, Lerp, Track, Vec2};
+
+const FPS: u32 = 60;
+const SECONDS: u32 = 60;
+
+fn main() -> Result<()> {
+    // One keyframe per frame for a 60s clip: 3600 keyframes.
+    let frames = FPS * SECONDS;
+    let dt = 1.0 / FPS as f32;
+    let keys = (0..=frames)
+        .map(|i| {
+            let t = i as f32 * dt;
+            Keyframe::new(t, Vec2 { x: t.sin() * 200.0, y: t.cos() * 120.0 }, Easing::Linear)
+        })
+        .collect();
+    let track = Track::new(keys)?;
+    let times: Vec<f32> = (0..frames).map(|i| (i as f32 + 0.5) * dt).collect();
+
+    println!("track: {} keyframes, {} sequential samples", track.keyframes().len(), times.len());
+
+    let linear = bench("linear scan (old)", || {
+        for &t in &times {
+            black_box(sample_linear_scan(&track, t));
+        }
+    });
+    let binary = bench("Track::sample (binary search)", || {
+        for &t in &times {
+            black_box(track.sample(t));
+        }
+    });
+    let cursor = bench("TrackCursor::sample", || {
+        let mut cursor = track.cursor();
+        for &t in &times {
+            black_box(cursor.sample(t));
+        }
+    });
+
+    println!(
+        "speedup vs linear scan: binary {:.1}x, cursor {:.1}x",
+        linear.as_secs_f64() / binary.as_secs_f64(),
+        linear.as_secs_f64() / cursor.as_secs_f64()
+    );
+
+    // Both fast paths must agree with the reference.
+    let mut cursor = track.cursor();
+    for &t in &times {
+        let expected = sample_linear_scan(&track, t);
+        for got in [track.sample(t), cursor.sample(t)] {
+            assert!((got.x - expected.x).abs() < 1e-4 && (got.y - expected.y).abs() < 1e-4);
+        }
+    }
+    Ok(())
+}
+
+// Best of several runs of one full pass.
+fn bench(name: &str, mut pass: impl FnMut()) -> Duration {
+    pass();
+    let best = (0..10)
+        .map(|_| {
+            let start = Instant::now();
+            pass();
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default();
+    println!("{name:<32} {:>10.3} ms/pass", best.as_secs_f64() * 1000.0);
+    best
+}
+
+// The previous O(n) lookup, kept here as the baseline.
+fn sample_linear_scan(track: &Track<Vec2>, t: f32) -> Vec2 {
+    let keys = track.keyframes();
+    let first = &keys[0];
+    let last = &keys[keys.len() - 1];
+    if t <= first.time {
+        return first.value;
+    }
+    if t >= last.time {
+        return last.value;
+    }
+
+    let mut idx = 0;
+    for i in 0..keys.len() - 1 {
+        if t >= keys[i].time && t < keys[i + 1].time {
+            idx = i;
+            break;
+        }
+    }
+    let k0 = &keys[idx];
+    let k1 = &keys[idx + 1];
+    let u = (t - k0.time) / (k1.time - k0.time);
+    Vec2::lerp(k0.value, k1.value, k0.easing_to_next.apply(u))
+}
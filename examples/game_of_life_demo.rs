@@ -5,7 +5,7 @@ use anyhow::{bail, Result};
 
 use script_2_script::{
     AnimatedTransform, Clip, Color, FfmpegVideoEncoder, Layer, Object, RaylibPreview,
-    RaylibRender, Shape, Timeline, Transform, Vec2,
+    RaylibRender, Renderer, Shape, Timeline, Transform, Vec2,
 };
 
 fn main() -> Result<()> {
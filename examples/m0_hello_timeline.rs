@@ -5,7 +5,7 @@ use std::path::{Path, PathBuf};
 
 use script_2_script::{
     AnimatedTransform, Clip, Color, FfmpegVideoEncoder, ImageObject, Layer, Object, RaylibPreview,
-    RaylibRender, Shape, Timeline, Transform, Vec2,
+    RaylibRender, Renderer, Shape, Timeline, Transform, Vec2,
 };
 
 fn main() -> Result<()> {
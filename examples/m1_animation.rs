@@ -5,7 +5,7 @@ use anyhow::{bail, Result};
 
 use script_2_script::{
     AnimatedTransform, Clip, Color, Easing, FfmpegVideoEncoder, ImageObject, Keyframe, Layer,
-    Object, RaylibPreview, RaylibRender, Shape, Timeline, Track, Transform, Vec2,
+    Object, RaylibPreview, RaylibRender, Renderer, Shape, Timeline, Track, Transform, Vec2,
 };
 
 fn main() -> Result<()> {
@@ -39,6 +39,7 @@ fn main() -> Result<()> {
         scale: Track::from_constant(Vec2 { x: 1.0, y: 1.0 }),
         rotation: Track::from_constant(0.0),
         opacity: Track::from_constant(1.0),
+        anchor: None,
     };
 
     motion.add_clip(Clip::new(
@@ -66,6 +67,7 @@ fn main() -> Result<()> {
             Keyframe::new(3.2, 1.0, Easing::EaseOutCubic),
             Keyframe::new(4.0, 0.0, Easing::Linear),
         ])?,
+        anchor: None,
     };
 
     motion.add_clip(Clip::new(
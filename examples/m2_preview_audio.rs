@@ -4,8 +4,9 @@ use std::path::{Path, PathBuf};
 use anyhow::{bail, Result};
 
 use script_2_script::{
-    AnimatedTransform, AudioEngine, Clip, Color, Easing, FfmpegVideoEncoder, ImageObject, Keyframe,
-    Layer, Object, RaylibPreview, RaylibRender, Shape, Timeline, Track, Transform, Vec2,
+    AnimatedTransform, Clip, Color, Easing, FfmpegVideoEncoder, ImageObject, Keyframe, Layer,
+    MusicTrack, Object, PreviewAudio, RaylibPreview, RaylibRender, Renderer, SfxEvent, Shape,
+    Timeline, Track, Transform, Vec2,
 };
 
 fn main() -> Result<()> {
@@ -37,6 +38,7 @@ fn main() -> Result<()> {
         scale: Track::from_constant(Vec2 { x: 1.0, y: 1.0 }),
         rotation: Track::from_constant(0.0),
         opacity: Track::from_constant(1.0),
+        anchor: None,
     };
 
     motion.add_clip(Clip::new(
@@ -76,22 +78,30 @@ fn main() -> Result<()> {
         })?;
         encoder.finish()
     } else {
-        // Preview window uses fixed-dt sampling; audio is updated each frame.
+        // Preview window uses fixed-dt sampling; audio follows preview time, so
+        // pausing (space), seeking (left/right) and restarting (r) stay in sync.
         let preview = RaylibPreview::new(800, 600, Color::rgb(16, 16, 20));
-        let mut audio = AudioEngine::new("assets/background.mp3", "assets/border.ogg")?;
-        audio.start_background(true);
-
-        // Play a bounce sound every time the integer second increases.
-        let mut last_second = args.start_time.floor() as i32;
-        preview.run_with(&timeline, args.start_time, args.end_time, |t| {
-            audio.update();
-            let current = t.floor() as i32;
-            if current > last_second {
-                audio.play_bounce();
-                last_second = current;
-            }
-            Ok(())
-        })
+        let music = MusicTrack {
+            path: PathBuf::from("assets/background.mp3"),
+            start: 0.0,
+            end: timeline.duration,
+            looped: true,
+            volume: 0.25,
+            source_offset: 0.0,
+            fade_in: 0.5,
+            fade_out: 0.5,
+            crossfade: 0.0,
+            automation: None,
+        };
+
+        // A bounce sound at every whole second, from the same event list an
+        // offline render would mix.
+        let bounces = (1..timeline.duration.ceil() as u32)
+            .map(|second| SfxEvent::new("assets/border.ogg", second as f32, 0.7))
+            .collect::<Vec<_>>();
+
+        let mut audio = PreviewAudio::new(&[music], &[], &bounces)?;
+        preview.run_with_audio(&timeline, args.start_time, args.end_time, &mut audio)
     }
 }
 
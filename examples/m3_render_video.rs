@@ -5,7 +5,7 @@ use anyhow::{bail, Context, Result};
 
 use script_2_script::{
     AnimatedTransform, Clip, Color, Easing, FfmpegVideoEncoder, Keyframe, Layer, Object,
-    RaylibRender, Shape, Timeline, Track, Transform, Vec2,
+    RaylibRender, Renderer, Shape, SoftwareRender, Timeline, Track, Transform, Vec2,
 };
 
 fn main() -> Result<()> {
@@ -80,6 +80,7 @@ fn main() -> Result<()> {
                 scale: Track::from_constant(Vec2 { x: 1.0, y: 1.0 }),
                 rotation: Track::from_constant(0.0),
                 opacity,
+                anchor: None,
             },
             timeline.duration,
         )?);
@@ -97,9 +98,13 @@ fn main() -> Result<()> {
         output_path.clone()
     };
 
-    let mut renderer = RaylibRender::new(800, 600, Color::rgb(16, 16, 20))?;
+    // CPU rasterizer with --software: no window or GPU needed (CI, render servers).
+    let mut renderer: Box<dyn Renderer> = if args.software {
+        Box::new(SoftwareRender::new(800, 600, Color::rgb(16, 16, 20))?)
+    } else {
+        Box::new(RaylibRender::new(800, 600, Color::rgb(16, 16, 20))?)
+    };
     let mut encoder = FfmpegVideoEncoder::start(800, 600, timeline.fps, &temp_path)?;
-
     renderer.render_timeline_rgba(&timeline, args.start_time, args.end_time, |_t, rgba| {
         encoder.write_frame(rgba)
     })?;
@@ -223,6 +228,7 @@ struct RenderArgs {
     end_time: f32,
     output: Option<PathBuf>,
     keep_temp: bool,
+    software: bool,
 }
 
 impl RenderArgs {
@@ -231,6 +237,7 @@ impl RenderArgs {
         let mut end_time = duration;
         let mut output = None;
         let mut keep_temp = false;
+        let mut software = false;
 
         let mut args = env::args().skip(1);
         while let Some(arg) = args.next() {
@@ -255,6 +262,9 @@ impl RenderArgs {
                 "--keep-temp" => {
                     keep_temp = true;
                 }
+                "--software" => {
+                    software = true;
+                }
                 other => bail!("unknown argument: {other}"),
             }
         }
@@ -268,6 +278,7 @@ impl RenderArgs {
             end_time,
             output,
             keep_temp,
+            software,
         })
     }
 
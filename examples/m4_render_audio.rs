@@ -5,7 +5,8 @@ use anyhow::{bail, Result};
 
 use script_2_script::{
     AnimatedTransform, Clip, Color, Easing, FfmpegVideoEncoder, Keyframe, Layer, MusicTrack,
-    Object, RaylibRender, Shape, SfxEvent, Timeline, Track, Transform, Vec2,
+    Object, RaylibRender, Renderer, Shape, SfxEvent, SfxVariation, SoftwareRender, Timeline, Track,
+    Transform, Vec2,
 };
 use script_2_script::{mux_video_audio, render_audio_wav, trim_audio};
 
@@ -49,13 +50,18 @@ fn main() -> Result<()> {
     let samples =
         build_bounce_samples(timeline.duration, timeline.fps, start_pos, start_vel, bounds);
 
+    // Each bounce gets a slightly different pitch and level so repeats don't
+    // sound mechanical; the seed keeps renders identical.
     let sfx_events = samples
         .bounce_times
         .iter()
         .map(|time| SfxEvent {
-            path: PathBuf::from("assets/border.ogg"),
-            time: *time,
-            volume: 0.7,
+            variation: Some(SfxVariation {
+                seed: 7,
+                pitch: 1.5,
+                volume_db: 2.0,
+            }),
+            ..SfxEvent::new("assets/border.ogg", *time, 0.7)
         })
         .collect::<Vec<_>>();
 
@@ -91,6 +97,7 @@ fn main() -> Result<()> {
                 scale: Track::from_constant(Vec2 { x: 1.0, y: 1.0 }),
                 rotation: Track::from_constant(0.0),
                 opacity,
+                anchor: None,
             },
             timeline.duration,
         )?);
@@ -106,23 +113,33 @@ fn main() -> Result<()> {
     let audio_full = output_path.with_file_name("audio_full.wav");
     let audio_clip = output_path.with_file_name("audio_clip.wav");
 
-    let mut renderer = RaylibRender::new(800, 600, Color::rgb(16, 16, 20))?;
+    // CPU rasterizer with --software: no window or GPU needed (CI, render servers).
+    let mut renderer: Box<dyn Renderer> = if args.software {
+        Box::new(SoftwareRender::new(800, 600, Color::rgb(16, 16, 20))?)
+    } else {
+        Box::new(RaylibRender::new(800, 600, Color::rgb(16, 16, 20))?)
+    };
     let mut encoder = FfmpegVideoEncoder::start(800, 600, timeline.fps, &temp_video)?;
     renderer.render_timeline_rgba(&timeline, args.start_time, args.end_time, |_t, rgba| {
         encoder.write_frame(rgba)
     })?;
     encoder.finish()?;
 
-    // Background music track (looped to cover the full timeline).
+    // Background music track (looped to cover the full timeline), faded in and out.
     let music = MusicTrack {
         path: PathBuf::from("assets/background.mp3"),
         start: 0.0,
         end: timeline.duration,
         looped: true,
         volume: 0.25,
+        source_offset: 0.0,
+        fade_in: 1.5,
+        fade_out: 2.0,
+        crossfade: 0.0,
+        automation: None,
     };
 
-    render_audio_wav(&music, &sfx_events, timeline.duration, &audio_full)?;
+    render_audio_wav(&[music], &sfx_events, timeline.duration, &audio_full)?;
 
     // Trim audio if we rendered only a segment of the full timeline.
     let audio_for_mux = if args.start_time == 0.0 && args.end_time == timeline.duration {
@@ -149,6 +166,7 @@ struct RenderArgs {
     end_time: f32,
     output: Option<PathBuf>,
     keep_temp: bool,
+    software: bool,
 }
 
 impl RenderArgs {
@@ -157,6 +175,7 @@ impl RenderArgs {
         let mut end_time = duration;
         let mut output = None;
         let mut keep_temp = false;
+        let mut software = false;
 
         let mut args = env::args().skip(1);
         while let Some(arg) = args.next() {
@@ -181,6 +200,9 @@ impl RenderArgs {
                 "--keep-temp" => {
                     keep_temp = true;
                 }
+                "--software" => {
+                    software = true;
+                }
                 other => bail!("unknown argument: {other}"),
             }
         }
@@ -194,6 +216,7 @@ impl RenderArgs {
             end_time,
             output,
             keep_temp,
+            software,
         })
     }
 
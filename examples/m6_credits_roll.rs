@@ -5,7 +5,8 @@ use anyhow::{bail, Result};
 
 use script_2_script::{
     AnimatedTransform, Clip, Color, FontFamily, FontSource, FfmpegVideoEncoder, Layer, Object,
-    RaylibPreview, RaylibRender, StyledText, TextObject, Timeline, Track, Vec2,
+    RaylibPreview, RaylibRender, Renderer, StyledText, TextAlign, TextObject, Timeline, Track,
+    Vec2, VerticalAlign,
 };
 
 fn main() -> Result<()> {
@@ -39,6 +40,8 @@ fn main() -> Result<()> {
         max_width: 560.0,
         color: Color::rgb(230, 230, 230),
         line_spacing: 6.0,
+        align: TextAlign::Left,
+        vertical_align: VerticalAlign::Top,
     };
 
     let start_y = -300.0;
@@ -53,6 +56,7 @@ fn main() -> Result<()> {
         scale: Track::from_constant(Vec2 { x: 1.0, y: 1.0 }),
         rotation: Track::from_constant(0.0),
         opacity: Track::from_constant(1.0),
+        anchor: None,
     };
 
     let mut layer = Layer::new("credits");
@@ -0,0 +1,58 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+
+use script_2_script::{Color, FfmpegVideoEncoder, Project, RaylibPreview, RaylibRender, Renderer};
+
+fn main() -> Result<()> {
+    let args = Args::from_env()?;
+
+    // Load and validate the whole video from data: timeline, audio and base video.
+    let project = Project::load(&args.project)?;
+    let timeline = &project.timeline;
+    println!(
+        "loaded {}: {:.1}s @ {} fps, {} layers, {} sfx events",
+        args.project.display(),
+        timeline.duration,
+        timeline.fps,
+        timeline.layers.len(),
+        project.sfx.len()
+    );
+
+    if args.render {
+        let output_path = PathBuf::from("output/m7_project_file.mp4");
+        std::fs::create_dir_all(output_path.parent().unwrap_or(Path::new(".")))?;
+        let mut renderer = RaylibRender::new(800, 600, Color::rgb(16, 16, 20))?;
+        let mut encoder = FfmpegVideoEncoder::start(800, 600, timeline.fps, &output_path)?;
+        renderer.render_timeline_rgba(timeline, 0.0, timeline.duration, |_t, rgba| {
+            encoder.write_frame(rgba)
+        })?;
+        encoder.finish()
+    } else {
+        let preview = RaylibPreview::new(800, 600, Color::rgb(16, 16, 20));
+        preview.run(timeline)
+    }
+}
+
+struct Args {
+    project: PathBuf,
+    render: bool,
+}
+
+impl Args {
+    fn from_env() -> Result<Self> {
+        let mut project = PathBuf::from("examples/projects/hello.json");
+        let mut render = false;
+
+        for arg in env::args().skip(1) {
+            match arg.as_str() {
+                "--render" => render = true,
+                other if other.starts_with("--") => bail!("unknown argument: {other}"),
+                other => project = PathBuf::from(other),
+            }
+        }
+
+        Ok(Self { project, render })
+    }
+}
@@ -0,0 +1,423 @@
+use std::f32::consts::PI;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::audio::decode::decode_file;
+use crate::audio::{AudioBuffer, Mixer, MusicTrack, SfxEvent};
+use crate::scene::{Easing, Keyframe, Track};
+
+// Analysis runs on a lower rate than the mix; beats and envelopes don't need
+// the top octave.
+pub const ANALYSIS_SAMPLE_RATE: u32 = 22_050;
+
+const FRAME: usize = 1024;
+const HOP: usize = 512;
+const MIN_BPM: f32 = 60.0;
+const MAX_BPM: f32 = 200.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioAnalysis {
+    pub envelope: RmsEnvelope,
+    pub onsets: Vec<Onset>,
+    // `None` when there are too few onsets to find a pulse.
+    pub beats: Option<BeatGrid>,
+}
+
+// RMS level sampled `rate` times per second from t = 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RmsEnvelope {
+    pub rate: u32,
+    pub values: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Onset {
+    pub time: f32,
+    // Spectral flux at the onset, relative to the strongest onset (0..=1).
+    pub strength: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeatGrid {
+    pub bpm: f32,
+    pub beats: Vec<f32>,
+}
+
+// Decodes `track`'s source and analyzes it as placed on the timeline, so every
+// time in the result is in timeline seconds. Volume and fades are ignored.
+pub fn analyze_music(track: &MusicTrack) -> Result<AudioAnalysis> {
+    let source = decode_file(&track.path, ANALYSIS_SAMPLE_RATE)
+        .with_context(|| format!("failed to analyze {}", track.path.display()))?;
+    let mut mixer = Mixer::new(track.end, ANALYSIS_SAMPLE_RATE)?;
+    mixer.add_segment(
+        &source,
+        track.start,
+        track.end,
+        track.source_offset,
+        1.0,
+        track.looped,
+    );
+    Ok(analyze(&mixer.finish()))
+}
+
+pub fn analyze(buffer: &AudioBuffer) -> AudioAnalysis {
+    let mono: Vec<f32> = buffer
+        .samples
+        .chunks_exact(2)
+        .map(|frame| (frame[0] + frame[1]) * 0.5)
+        .collect();
+    let sample_rate = buffer.sample_rate;
+    let flux = spectral_flux(&mono);
+    let frame_rate = sample_rate as f32 / HOP as f32;
+    let frame_time = |idx: f32| (idx * HOP as f32 + FRAME as f32 / 2.0) / sample_rate as f32;
+
+    let onsets = pick_onsets(&flux, frame_rate)
+        .into_iter()
+        .map(|idx| Onset {
+            time: frame_time(idx as f32),
+            strength: flux[idx],
+        })
+        .collect::<Vec<_>>();
+
+    let beats = estimate_beats(&flux, frame_rate).and_then(|(lag, phase)| {
+        let first_onset = onsets.first()?.time;
+        let period = lag / frame_rate;
+        // Align the grid to the first beat near the start of the music.
+        let earliest = first_onset - period * 0.5;
+        let mut time = frame_time(phase);
+        while time < earliest {
+            time += period;
+        }
+        while time - period >= earliest {
+            time -= period;
+        }
+        let end = buffer.duration();
+        let mut beats = Vec::new();
+        while time < end {
+            beats.push(time);
+            time += period;
+        }
+        Some(BeatGrid {
+            bpm: 60.0 / period,
+            beats,
+        })
+    });
+
+    AudioAnalysis {
+        envelope: rms_envelope(&mono, sample_rate, 100),
+        onsets,
+        beats,
+    }
+}
+
+pub fn rms_envelope(mono: &[f32], sample_rate: u32, rate: u32) -> RmsEnvelope {
+    let window = (sample_rate / rate).max(1) as usize;
+    let values = mono
+        .chunks(window)
+        .map(|chunk| (chunk.iter().map(|s| s * s).sum::<f32>() / chunk.len() as f32).sqrt())
+        .collect();
+    RmsEnvelope { rate, values }
+}
+
+impl RmsEnvelope {
+    // Scaled so the loudest window is 1.0, for driving opacity, scale and so on.
+    pub fn normalized(&self) -> RmsEnvelope {
+        let peak = self.values.iter().copied().fold(0.0, f32::max);
+        let scale = if peak > 0.0 { 1.0 / peak } else { 0.0 };
+        RmsEnvelope {
+            rate: self.rate,
+            values: self.values.iter().map(|v| v * scale).collect(),
+        }
+    }
+
+    // One linear keyframe per window, at the window's center.
+    pub fn to_track(&self) -> Result<Track<f32>> {
+        let dt = 1.0 / self.rate as f32;
+        let keys = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(idx, &value)| Keyframe::new((idx as f32 + 0.5) * dt, value, Easing::Linear))
+            .collect();
+        Track::new(keys)
+    }
+}
+
+impl AudioAnalysis {
+    pub fn onset_times(&self) -> Vec<f32> {
+        self.onsets.iter().map(|onset| onset.time).collect()
+    }
+
+    pub fn beat_times(&self) -> &[f32] {
+        self.beats.as_ref().map_or(&[], |grid| &grid.beats)
+    }
+}
+
+impl BeatGrid {
+    pub fn period(&self) -> f32 {
+        60.0 / self.bpm
+    }
+
+    // Every `every`-th beat starting from `offset`, e.g. (4, 0) for bar starts in 4/4.
+    pub fn every(&self, every: usize, offset: usize) -> Vec<f32> {
+        self.beats
+            .iter()
+            .skip(offset)
+            .step_by(every.max(1))
+            .copied()
+            .collect()
+    }
+}
+
+// A track that jumps to 1.0 at each time and eases back to 0.0 over `decay`
+// seconds (or until the next time, if that comes first). Drive opacity, scale
+// or position offsets with it to pulse on beats or onsets.
+pub fn pulse_track(times: &[f32], decay: f32) -> Result<Track<f32>> {
+    let mut keys = Vec::with_capacity(times.len() * 2 + 1);
+    if times.first().is_none_or(|&t| t > 0.0) {
+        keys.push(Keyframe::new(0.0, 0.0, Easing::Hold));
+    }
+    for (idx, &time) in times.iter().enumerate() {
+        keys.push(Keyframe::new(time, 1.0, Easing::EaseOutCubic));
+        let mut rest = time + decay.max(0.0);
+        if let Some(&next) = times.get(idx + 1) {
+            rest = rest.min(time + (next - time) * 0.9);
+        }
+        if rest > time {
+            keys.push(Keyframe::new(rest, 0.0, Easing::Hold));
+        }
+    }
+    Track::new(keys)
+}
+
+// Marker events at `times`, e.g. to audition a beat grid with a click sound or
+// to hand onsets to code that already consumes `SfxEvent` lists.
+pub fn markers(times: &[f32], path: &Path, volume: f32) -> Vec<SfxEvent> {
+    times
+        .iter()
+        .map(|&time| SfxEvent::new(path, time, volume))
+        .collect()
+}
+
+// Log-magnitude spectral flux per hop, normalized to a peak of 1.0.
+fn spectral_flux(mono: &[f32]) -> Vec<f32> {
+    if mono.len() < FRAME {
+        return Vec::new();
+    }
+    let window: Vec<f32> = (0..FRAME)
+        .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / FRAME as f32).cos())
+        .collect();
+    let frames = (mono.len() - FRAME) / HOP + 1;
+    let mut previous = vec![0.0f32; FRAME / 2];
+    let mut flux = Vec::with_capacity(frames);
+    let mut re = vec![0.0f32; FRAME];
+    let mut im = vec![0.0f32; FRAME];
+
+    for idx in 0..frames {
+        let start = idx * HOP;
+        for (i, (r, w)) in re.iter_mut().zip(&window).enumerate() {
+            *r = mono[start + i] * w;
+        }
+        im.fill(0.0);
+        fft(&mut re, &mut im);
+
+        let mut sum = 0.0;
+        for (bin, prev) in previous.iter_mut().enumerate() {
+            let magnitude = (1.0 + 100.0 * (re[bin] * re[bin] + im[bin] * im[bin]).sqrt()).ln();
+            if idx > 0 {
+                sum += (magnitude - *prev).max(0.0);
+            }
+            *prev = magnitude;
+        }
+        flux.push(sum);
+    }
+
+    let peak = flux.iter().copied().fold(0.0, f32::max);
+    if peak > 0.0 {
+        for value in &mut flux {
+            *value /= peak;
+        }
+    }
+    flux
+}
+
+// Local maxima that clear a moving-average threshold, at least 50 ms apart.
+fn pick_onsets(flux: &[f32], frame_rate: f32) -> Vec<usize> {
+    let context = (frame_rate * 0.1).ceil() as usize;
+    let min_gap = (frame_rate * 0.05).ceil() as usize;
+    let mut picked: Vec<usize> = Vec::new();
+    for idx in 1..flux.len() {
+        let lo = idx.saturating_sub(context);
+        let hi = (idx + context + 1).min(flux.len());
+        let local = &flux[lo..hi];
+        let mean = local.iter().sum::<f32>() / local.len() as f32;
+        let is_peak = local.iter().all(|&v| v <= flux[idx]);
+        if is_peak && flux[idx] > mean + 0.1 && picked.last().is_none_or(|&p| idx - p >= min_gap) {
+            picked.push(idx);
+        }
+    }
+    picked
+}
+
+// Tempo from the autocorrelation of the flux, weighted toward ~120 BPM to pick
+// the musically likely octave, then the phase whose beats land on the most
+// flux. Returns (period in frames, first beat frame).
+fn estimate_beats(flux: &[f32], frame_rate: f32) -> Option<(f32, f32)> {
+    let min_lag = (frame_rate * 60.0 / MAX_BPM).floor() as usize;
+    let max_lag = (frame_rate * 60.0 / MIN_BPM).ceil() as usize;
+    // The peak search needs a neighbour below `min_lag`; at such low frame
+    // rates there is no tempo resolution anyway.
+    if min_lag < 2 || flux.len() <= max_lag * 2 {
+        return None;
+    }
+    let mean = flux.iter().sum::<f32>() / flux.len() as f32;
+    let centered: Vec<f32> = flux.iter().map(|v| v - mean).collect();
+    let autocorrelation = |lag: usize| -> f32 {
+        centered[lag..]
+            .iter()
+            .zip(&centered)
+            .map(|(a, b)| a * b)
+            .sum::<f32>()
+            / (centered.len() - lag) as f32
+    };
+    let scores: Vec<f32> = (min_lag - 1..=max_lag + 1).map(autocorrelation).collect();
+    let weighted = |i: usize| {
+        let bpm = frame_rate * 60.0 / (min_lag - 1 + i) as f32;
+        scores[i] * (-0.5 * (bpm / 120.0).log2().powi(2)).exp()
+    };
+    let best = (1..scores.len() - 1).max_by(|&a, &b| weighted(a).total_cmp(&weighted(b)))?;
+    if scores[best] <= 0.0 {
+        return None;
+    }
+    // Parabolic interpolation around the peak for sub-frame tempo.
+    let (a, b, c) = (scores[best - 1], scores[best], scores[best + 1]);
+    let denom = a - 2.0 * b + c;
+    let shift = if denom.abs() > f32::EPSILON {
+        (0.5 * (a - c) / denom).clamp(-0.5, 0.5)
+    } else {
+        0.0
+    };
+    let lag = (min_lag - 1 + best) as f32 + shift;
+
+    let phase = (0..lag.ceil() as usize).max_by(|&a, &b| {
+        beat_sum(flux, a as f32, lag).total_cmp(&beat_sum(flux, b as f32, lag))
+    })?;
+    Some((lag, phase as f32))
+}
+
+fn beat_sum(flux: &[f32], phase: f32, lag: f32) -> f32 {
+    let mut sum = 0.0;
+    let mut pos = phase;
+    while (pos.round() as usize) < flux.len() {
+        sum += flux[pos.round() as usize];
+        pos += lag;
+    }
+    sum
+}
+
+// In-place iterative radix-2 FFT; `re.len()` must be a power of two.
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let tr = re[b] * cos - im[b] * sin;
+                let ti = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Short decaying noise bursts every `period` seconds, starting at `first`.
+    fn click_track(bpm: f32, first: f32, seconds: f32) -> AudioBuffer {
+        let rate = ANALYSIS_SAMPLE_RATE;
+        let mut mono = vec![0.0f32; (seconds * rate as f32) as usize];
+        let mut seed = 1u32;
+        let mut time = first;
+        while time < seconds {
+            let start = (time * rate as f32) as usize;
+            for i in 0..400.min(mono.len().saturating_sub(start)) {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                let noise = (seed >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0;
+                mono[start + i] = noise * (-(i as f32) / 80.0).exp();
+            }
+            time += 60.0 / bpm;
+        }
+        AudioBuffer::from_interleaved(rate, 1, &mono).unwrap()
+    }
+
+    #[test]
+    fn finds_onsets_and_tempo_of_a_click_track() {
+        let analysis = analyze(&click_track(120.0, 0.25, 8.0));
+
+        let onsets = analysis.onset_times();
+        assert_eq!(onsets.len(), 16, "{onsets:?}");
+        for (idx, time) in onsets.iter().enumerate() {
+            let expected = 0.25 + idx as f32 * 0.5;
+            assert!((time - expected).abs() < 0.03, "onset {idx} at {time}");
+        }
+
+        let grid = analysis.beats.as_ref().unwrap();
+        assert!((grid.bpm - 120.0).abs() < 1.0, "{} bpm", grid.bpm);
+        assert!((grid.beats[0] - 0.25).abs() < 0.03, "first beat {}", grid.beats[0]);
+        assert_eq!(grid.every(4, 0).len(), 4);
+    }
+
+    #[test]
+    fn low_frame_rates_have_no_beat_grid() {
+        let flux: Vec<f32> = (0..64).map(|i| (i % 2) as f32).collect();
+        assert_eq!(estimate_beats(&flux, 2.0), None);
+        assert_eq!(estimate_beats(&flux, 5.0), None);
+
+        let analysis = analyze(&AudioBuffer::from_interleaved(1000, 1, &[0.5; 8000]).unwrap());
+        assert!(analysis.beats.is_none());
+    }
+
+    #[test]
+    fn converts_analysis_to_tracks_and_markers() {
+        let envelope = RmsEnvelope {
+            rate: 10,
+            values: vec![0.0, 0.5, 0.25],
+        };
+        let track = envelope.normalized().to_track().unwrap();
+        assert_eq!(track.sample(0.15), 1.0);
+
+        let pulse = pulse_track(&[0.5, 0.6], 0.25).unwrap();
+        assert_eq!(pulse.sample(0.2), 0.0);
+        assert_eq!(pulse.sample(0.5), 1.0);
+        assert_eq!(pulse.sample(0.6), 1.0);
+        assert_eq!(pulse.sample(0.9), 0.0);
+
+        let events = markers(&[1.0, 2.0], Path::new("click.wav"), 0.5);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].time, 2.0);
+    }
+}
@@ -0,0 +1,113 @@
+use std::fs::File;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error;
+use symphonia::core::formats::{FormatOptions, FormatReader};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::audio::mixer::{AudioBuffer, DEFAULT_SAMPLE_RATE};
+
+// Decodes an audio file (mp3, ogg/vorbis, flac, wav) to stereo f32 PCM at
+// `sample_rate`, so the mixer can add it without further conversion.
+pub fn decode_file(path: &Path, sample_rate: u32) -> Result<AudioBuffer> {
+    let mut format = open_format(path)?;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .with_context(|| format!("no audio track in {}", path.display()))?;
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .with_context(|| format!("unsupported audio codec: {}", path.display()))?;
+
+    let mut source_rate = track.codec_params.sample_rate.unwrap_or(sample_rate);
+    let mut channels = track
+        .codec_params
+        .channels
+        .map(|channels| channels.count())
+        .unwrap_or(2);
+    let mut samples = Vec::new();
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::IoError(err)) if err.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read {}", path.display()));
+            }
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        match decoder.decode(&packet) {
+            Ok(decoded) => {
+                let spec = *decoded.spec();
+                source_rate = spec.rate;
+                channels = spec.channels.count();
+                let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+                buffer.copy_interleaved_ref(decoded);
+                samples.extend_from_slice(buffer.samples());
+            }
+            // Corrupt frames are skipped, like ffmpeg does.
+            Err(Error::DecodeError(_)) => continue,
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to decode {}", path.display()));
+            }
+        }
+    }
+
+    if channels == 0 {
+        bail!("audio has no channels: {}", path.display());
+    }
+    let decoded = AudioBuffer::from_interleaved(source_rate, channels, &samples)?;
+    Ok(decoded.resampled(sample_rate))
+}
+
+// Length of an audio file in seconds. Reads it from the container header when
+// the format records a frame count, and decodes the file otherwise.
+pub fn probe_duration(path: &Path) -> Result<f32> {
+    let format = open_format(path)?;
+    let params = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .map(|track| &track.codec_params)
+        .with_context(|| format!("no audio track in {}", path.display()))?;
+    if let (Some(frames), Some(rate)) = (params.n_frames, params.sample_rate)
+        && rate > 0
+    {
+        return Ok(frames as f32 / rate as f32);
+    }
+    Ok(decode_file(path, DEFAULT_SAMPLE_RATE)?.duration())
+}
+
+fn open_format(path: &Path) -> Result<Box<dyn FormatReader>> {
+    if !path.exists() {
+        bail!("audio file not found: {}", path.display());
+    }
+    let file =
+        File::open(path).with_context(|| format!("failed to open audio: {}", path.display()))?;
+    let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(ext);
+    }
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .with_context(|| format!("unsupported audio format: {}", path.display()))?;
+    Ok(probed.format)
+}
@@ -0,0 +1,187 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::audio::AudioBuffer;
+use crate::scene::{Easing, Keyframe, Track};
+
+// Sidechain-style ducking: music drops by `amount_db` while the key signal is
+// above `threshold_db`, ramping down over `attack` and back up over `release`
+// seconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ducking {
+    pub amount_db: f32,
+    pub attack: f32,
+    pub release: f32,
+    #[serde(default = "default_threshold")]
+    pub threshold_db: f32,
+    #[serde(default)]
+    pub key: DuckKey,
+}
+
+fn default_threshold() -> f32 {
+    -40.0
+}
+
+// Which part of the mix drives the ducking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DuckKey {
+    // Every SFX event, including voice lines placed as SFX.
+    Sfx,
+    // `DialogueClip`s only.
+    Dialogue,
+    // Dialogue and SFX together, so narration ducks the music whichever way
+    // it was placed.
+    #[default]
+    All,
+}
+
+impl Ducking {
+    // Detector resolution: the key level is measured in 10 ms windows.
+    pub const CURVE_RATE: u32 = 100;
+
+    pub fn new(amount_db: f32, attack: f32, release: f32) -> Self {
+        Self {
+            amount_db,
+            attack,
+            release,
+            threshold_db: default_threshold(),
+            key: DuckKey::default(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.amount_db < 0.0 {
+            bail!("ducking amount_db must be >= 0");
+        }
+        if self.attack < 0.0 || self.release < 0.0 {
+            bail!("ducking attack and release must be >= 0");
+        }
+        Ok(())
+    }
+
+    // Music gain over time for the given key signal.
+    pub fn curve(&self, key: &AudioBuffer) -> DuckingCurve {
+        let window = (key.sample_rate / Self::CURVE_RATE).max(1) as usize;
+        let floor = db_to_gain(-self.amount_db);
+        let dt = 1.0 / Self::CURVE_RATE as f32;
+        let step = |ramp: f32| if ramp > 0.0 { (1.0 - floor) * dt / ramp } else { f32::INFINITY };
+        let (down, up) = (step(self.attack), step(self.release));
+        let threshold = db_to_gain(self.threshold_db);
+
+        let mut gain = 1.0f32;
+        let mut gains = Vec::with_capacity(key.frames() / window + 2);
+        gains.push(gain);
+        for block in key.samples.chunks(window * 2) {
+            let power = block.iter().map(|s| s * s).sum::<f32>() / block.len() as f32;
+            gain = if power.sqrt() > threshold {
+                (gain - down).max(floor)
+            } else {
+                (gain + up).min(1.0)
+            };
+            gains.push(gain);
+        }
+
+        DuckingCurve {
+            rate: Self::CURVE_RATE,
+            gains,
+        }
+    }
+}
+
+// Music gain sampled `rate` times per second, starting at t = 0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuckingCurve {
+    pub rate: u32,
+    pub gains: Vec<f32>,
+}
+
+impl DuckingCurve {
+    pub fn sample(&self, t: f32) -> f32 {
+        let pos = t.max(0.0) * self.rate as f32;
+        let idx = pos.floor() as usize;
+        match (self.gains.get(idx), self.gains.get(idx + 1)) {
+            (Some(a), Some(b)) => a + (b - a) * (pos - idx as f32),
+            (Some(a), None) => *a,
+            _ => self.gains.last().copied().unwrap_or(1.0),
+        }
+    }
+
+    // Scales every frame of `buffer` by the curve.
+    pub fn apply(&self, buffer: &mut AudioBuffer) {
+        let rate = buffer.sample_rate as f32;
+        for (idx, frame) in buffer.samples.chunks_exact_mut(2).enumerate() {
+            let gain = self.sample(idx as f32 / rate);
+            frame[0] *= gain;
+            frame[1] *= gain;
+        }
+    }
+
+    // The curve as linear keyframes, keeping only the points where the slope
+    // changes, so it can be inspected or reused like any other `Track<f32>`.
+    pub fn to_track(&self) -> Result<Track<f32>> {
+        let dt = 1.0 / self.rate as f32;
+        let last = self.gains.len().saturating_sub(1);
+        let keys = self
+            .gains
+            .iter()
+            .enumerate()
+            .filter(|&(idx, &gain)| {
+                idx == 0
+                    || idx == last
+                    || (self.gains[idx + 1] - 2.0 * gain + self.gains[idx - 1]).abs() > 1e-6
+            })
+            .map(|(idx, &gain)| Keyframe::new(idx as f32 * dt, gain, Easing::Linear))
+            .collect();
+        Track::new(keys)
+    }
+
+    // `time,gain,gain_db` rows at the curve's keyframes.
+    pub fn to_csv(&self) -> Result<String> {
+        let mut csv = String::from("time,gain,gain_db\n");
+        for key in self.to_track()?.keyframes() {
+            let db = 20.0 * key.value.max(1e-6).log10();
+            writeln!(csv, "{:.3},{:.4},{:.2}", key.time, key.value, db)?;
+        }
+        Ok(csv)
+    }
+
+    pub fn write_csv(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_csv()?)
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+}
+
+pub fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ducks_with_attack_and_release_ramps() {
+        // One second of silence, half a second of key signal, then silence.
+        let mut key = AudioBuffer::silence(1000, 2000);
+        for sample in &mut key.samples[2000..3000] {
+            *sample = 0.5;
+        }
+        let ducking = Ducking::new(20.0, 0.1, 0.2);
+        let curve = ducking.curve(&key);
+
+        assert_eq!(curve.sample(0.9), 1.0);
+        assert!((curve.sample(1.05) - 0.55).abs() < 1e-4);
+        assert!((curve.sample(1.4) - 0.1).abs() < 1e-4);
+        assert!((curve.sample(1.6) - 0.55).abs() < 1e-4);
+        assert_eq!(curve.sample(1.8), 1.0);
+
+        let track = curve.to_track().unwrap();
+        assert!(track.keyframes().len() < 10, "{} keys", track.keyframes().len());
+        for t in [0.3, 1.05, 1.4, 1.6, 1.95] {
+            assert!((track.sample(t) - curve.sample(t)).abs() < 1e-4, "at {t}");
+        }
+    }
+}
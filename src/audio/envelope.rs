@@ -0,0 +1,106 @@
+use anyhow::Result;
+
+use crate::audio::MusicTrack;
+use crate::scene::{Easing, Keyframe, Track};
+
+// A gain ramp at one end of a placement. Plain fades are linear; crossfades
+// use sine easing so the overlapping pair keeps constant combined power.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fade {
+    pub duration: f32,
+    pub easing: Easing,
+}
+
+impl Fade {
+    pub const NONE: Fade = Fade::linear(0.0);
+
+    pub const fn linear(duration: f32) -> Self {
+        Self {
+            duration,
+            easing: Easing::Linear,
+        }
+    }
+}
+
+// Where a music track actually plays once crossfades are resolved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MusicPlacement {
+    pub start: f32,
+    pub end: f32,
+    pub fade_in: Fade,
+    pub fade_out: Fade,
+}
+
+impl MusicPlacement {
+    // Fade gain over placement-local time, 0 at `start`.
+    pub fn envelope(&self) -> Result<Track<f32>> {
+        fade_envelope(self.end - self.start, self.fade_in, self.fade_out)
+    }
+}
+
+// Resolves each track's playing span and fades, in input order. A track with
+// a crossfade extends the previous track (by start time) to `start +
+// crossfade`, fading it out while this one fades in.
+pub fn place_music(tracks: &[MusicTrack], duration: f32) -> Vec<MusicPlacement> {
+    let mut placements: Vec<MusicPlacement> = tracks
+        .iter()
+        .map(|track| MusicPlacement {
+            start: track.start,
+            end: track.end,
+            fade_in: Fade::linear(track.fade_in),
+            fade_out: Fade::linear(track.fade_out),
+        })
+        .collect();
+
+    let mut order: Vec<usize> = (0..tracks.len()).collect();
+    order.sort_by(|&a, &b| tracks[a].start.total_cmp(&tracks[b].start));
+    for pair in order.windows(2) {
+        let (prev, next) = (pair[0], pair[1]);
+        let crossfade = tracks[next].crossfade;
+        if crossfade <= 0.0 {
+            continue;
+        }
+        let start = tracks[next].start;
+        let handover = (start + crossfade).min(duration);
+        placements[prev].end = handover;
+        placements[prev].fade_out = Fade {
+            duration: handover - start,
+            easing: Easing::EaseInSine,
+        };
+        placements[next].fade_in = Fade {
+            duration: handover - start,
+            easing: Easing::EaseOutSine,
+        };
+    }
+    placements
+}
+
+// Unity gain over `length` seconds with ramps from and to silence. Ramps that
+// would overlap meet where the fade-in ends.
+pub fn fade_envelope(length: f32, fade_in: Fade, fade_out: Fade) -> Result<Track<f32>> {
+    let fade_in_end = fade_in.duration.clamp(0.0, length);
+    let fade_out_len = fade_out.duration.clamp(0.0, length);
+
+    let mut keys = Vec::new();
+    if fade_in_end > 0.0 {
+        keys.push(Keyframe::new(0.0, 0.0, fade_in.easing));
+        keys.push(Keyframe::new(fade_in_end, 1.0, Easing::Linear));
+    } else {
+        keys.push(Keyframe::new(0.0, 1.0, Easing::Linear));
+    }
+
+    if fade_out_len > 0.0 {
+        let fade_out_start = length - fade_out_len;
+        let last = keys.len() - 1;
+        if fade_out_start > keys[last].time {
+            keys.push(Keyframe::new(fade_out_start, 1.0, fade_out.easing));
+        } else {
+            keys[last].easing_to_next = fade_out.easing;
+        }
+        if length > keys[keys.len() - 1].time {
+            keys.push(Keyframe::new(length, 0.0, Easing::Linear));
+        }
+    }
+
+    Track::new(keys)
+}
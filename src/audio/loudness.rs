@@ -0,0 +1,390 @@
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+use std::fmt;
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::audio::ducking::db_to_gain;
+use crate::audio::AudioBuffer;
+
+// Integrated loudness goal for the final mix, with a true-peak ceiling that the
+// limiter enforces after the normalization gain.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LoudnessTarget {
+    pub integrated_lufs: f32,
+    #[serde(default = "default_ceiling")]
+    pub true_peak_dbtp: f32,
+}
+
+fn default_ceiling() -> f32 {
+    -1.0
+}
+
+impl LoudnessTarget {
+    // Typical for YouTube/Spotify-style platforms.
+    pub const STREAMING: LoudnessTarget = LoudnessTarget::new(-14.0, -1.0);
+    // EBU R128 broadcast.
+    pub const BROADCAST: LoudnessTarget = LoudnessTarget::new(-23.0, -1.0);
+
+    pub const fn new(integrated_lufs: f32, true_peak_dbtp: f32) -> Self {
+        Self {
+            integrated_lufs,
+            true_peak_dbtp,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if !self.integrated_lufs.is_finite() || self.integrated_lufs >= 0.0 {
+            bail!("loudness target must be below 0 LUFS");
+        }
+        if !self.true_peak_dbtp.is_finite() || self.true_peak_dbtp > 0.0 {
+            bail!("true peak ceiling must be <= 0 dBTP");
+        }
+        Ok(())
+    }
+}
+
+// BS.1770 / EBU R128 measurements. Silence (or audio shorter than one 400 ms
+// block) has an integrated loudness of -inf.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Loudness {
+    pub integrated_lufs: f32,
+    pub true_peak_dbtp: f32,
+}
+
+impl fmt::Display for Loudness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.1} LUFS integrated, {:.1} dBTP true peak",
+            self.integrated_lufs, self.true_peak_dbtp
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoudnessReport {
+    pub target: LoudnessTarget,
+    pub input: Loudness,
+    pub output: Loudness,
+    // Normalization gain before the limiter.
+    pub gain_db: f32,
+    // Largest gain reduction the limiter applied (0 when it never engaged).
+    pub limiter_reduction_db: f32,
+}
+
+impl fmt::Display for LoudnessReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "measured:   {}", self.input)?;
+        writeln!(
+            f,
+            "normalized: {:+.1} dB to {:.1} LUFS, limiter {:.1} dB at {:.1} dBTP ceiling",
+            self.gain_db,
+            self.target.integrated_lufs,
+            -self.limiter_reduction_db,
+            self.target.true_peak_dbtp
+        )?;
+        write!(f, "output:     {}", self.output)
+    }
+}
+
+pub fn measure_loudness(buffer: &AudioBuffer) -> Loudness {
+    Loudness {
+        integrated_lufs: integrated_loudness(buffer),
+        true_peak_dbtp: gain_to_db(true_peak(buffer)),
+    }
+}
+
+// Gains `buffer` to the target integrated loudness, then runs the true-peak
+// limiter. Silent buffers are left alone.
+pub fn normalize_loudness(buffer: &mut AudioBuffer, target: &LoudnessTarget) -> LoudnessReport {
+    let input = measure_loudness(buffer);
+    let gain_db = if input.integrated_lufs.is_finite() {
+        target.integrated_lufs - input.integrated_lufs
+    } else {
+        0.0
+    };
+    let gain = db_to_gain(gain_db);
+    for sample in &mut buffer.samples {
+        *sample *= gain;
+    }
+    let reduction = limit_true_peak(buffer, db_to_gain(target.true_peak_dbtp));
+
+    LoudnessReport {
+        target: *target,
+        input,
+        output: measure_loudness(buffer),
+        gain_db,
+        limiter_reduction_db: -gain_to_db(reduction),
+    }
+}
+
+fn gain_to_db(gain: f32) -> f32 {
+    20.0 * gain.log10()
+}
+
+// Gated integrated loudness (BS.1770-4): K-weighted mean square over 400 ms
+// blocks with 75% overlap, absolute gate at -70 LUFS, relative gate 10 LU
+// below the absolutely gated mean.
+pub fn integrated_loudness(buffer: &AudioBuffer) -> f32 {
+    let weighted = k_weighted(buffer);
+    let rate = buffer.sample_rate as usize;
+    let block = rate * 4 / 10;
+    let step = rate / 10;
+    let frames = buffer.frames();
+    if block == 0 || frames < block {
+        return f32::NEG_INFINITY;
+    }
+
+    // Per-frame energy summed over both channels (channel weights are 1 for L/R).
+    let energy: Vec<f64> = weighted.chunks_exact(2).map(|f| f[0] * f[0] + f[1] * f[1]).collect();
+    let mut prefix = Vec::with_capacity(frames + 1);
+    prefix.push(0.0);
+    for e in &energy {
+        prefix.push(prefix[prefix.len() - 1] + e);
+    }
+    let blocks: Vec<f64> = (0..=(frames - block) / step)
+        .map(|idx| (prefix[idx * step + block] - prefix[idx * step]) / block as f64)
+        .collect();
+
+    let loudness = |z: f64| -0.691 + 10.0 * z.log10();
+    let mean = |zs: &[f64]| zs.iter().sum::<f64>() / zs.len() as f64;
+    let absolute: Vec<f64> = blocks.into_iter().filter(|&z| loudness(z) > -70.0).collect();
+    if absolute.is_empty() {
+        return f32::NEG_INFINITY;
+    }
+    let relative_gate = loudness(mean(&absolute)) - 10.0;
+    let gated: Vec<f64> = absolute
+        .into_iter()
+        .filter(|&z| loudness(z) > relative_gate)
+        .collect();
+    loudness(mean(&gated)) as f32
+}
+
+// The BS.1770 pre-filter (high shelf then high pass) as two biquads,
+// derived for any sample rate.
+fn k_weighted(buffer: &AudioBuffer) -> Vec<f64> {
+    let fs = buffer.sample_rate as f64;
+
+    let k = (PI * 1_681.974_450_955_533 / fs).tan();
+    let q = 0.707_175_236_955_419_6;
+    let vh = 10f64.powf(3.999_843_853_973_347 / 20.0);
+    let vb = vh.powf(0.499_666_774_154_541_6);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad {
+        b: [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    };
+
+    let k = (PI * 38.135_470_876_024_44 / fs).tan();
+    let q = 0.500_327_037_323_877_3;
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    };
+
+    let mut out = vec![0.0; buffer.samples.len()];
+    for channel in 0..2 {
+        let mut stages = [shelf.state(), high_pass.state()];
+        for (idx, sample) in buffer.samples.iter().enumerate().skip(channel).step_by(2) {
+            let mut value = *sample as f64;
+            for stage in &mut stages {
+                value = stage.process(value);
+            }
+            out[idx] = value;
+        }
+    }
+    out
+}
+
+#[derive(Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+}
+
+impl Biquad {
+    fn state(self) -> BiquadState {
+        BiquadState {
+            filter: self,
+            x: [0.0; 2],
+            y: [0.0; 2],
+        }
+    }
+}
+
+struct BiquadState {
+    filter: Biquad,
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl BiquadState {
+    fn process(&mut self, x: f64) -> f64 {
+        let Biquad { b, a } = self.filter;
+        let y = b[0] * x + b[1] * self.x[0] + b[2] * self.x[1] - a[0] * self.y[0] - a[1] * self.y[1];
+        self.x = [x, self.x[0]];
+        self.y = [y, self.y[0]];
+        y
+    }
+}
+
+const OVERSAMPLE: usize = 4;
+const HALF_TAPS: usize = 8;
+
+// 4x oversampled peak magnitude (linear), as in BS.1770 Annex 2.
+pub fn true_peak(buffer: &AudioBuffer) -> f32 {
+    frame_peaks(buffer).into_iter().fold(0.0, f32::max)
+}
+
+// Largest oversampled magnitude between each frame and the next, over both channels.
+fn frame_peaks(buffer: &AudioBuffer) -> Vec<f32> {
+    let phases = interpolation_phases();
+    let frames = buffer.frames();
+    let at = |channel: usize, idx: isize| {
+        if idx < 0 || idx as usize >= frames {
+            0.0
+        } else {
+            buffer.samples[idx as usize * 2 + channel]
+        }
+    };
+
+    (0..frames)
+        .map(|n| {
+            let mut peak = 0.0f32;
+            for channel in 0..2 {
+                peak = peak.max(at(channel, n as isize).abs());
+                for taps in &phases {
+                    let mut value = 0.0;
+                    for (k, tap) in taps.iter().enumerate() {
+                        let idx = n as isize + k as isize + 1 - HALF_TAPS as isize;
+                        value += at(channel, idx) * tap;
+                    }
+                    peak = peak.max(value.abs());
+                }
+            }
+            peak
+        })
+        .collect()
+}
+
+// Hann-windowed sinc taps for the fractional positions 1/4, 2/4 and 3/4.
+fn interpolation_phases() -> Vec<[f32; HALF_TAPS * 2]> {
+    (1..OVERSAMPLE)
+        .map(|phase| {
+            let frac = phase as f64 / OVERSAMPLE as f64;
+            let mut taps = [0.0f32; HALF_TAPS * 2];
+            for (k, tap) in taps.iter_mut().enumerate() {
+                let x = frac - (k as f64 + 1.0 - HALF_TAPS as f64);
+                let sinc = (PI * x).sin() / (PI * x);
+                let window = 0.5 + 0.5 * (PI * x / HALF_TAPS as f64).cos();
+                *tap = (sinc * window) as f32;
+            }
+            taps
+        })
+        .collect()
+}
+
+// Lookahead limiter on the oversampled peaks. The gain is the sliding minimum
+// of the required reduction over the lookahead window, released exponentially
+// and then averaged over the window, so it is fully down at every peak and
+// ramps smoothly. Returns the smallest gain applied.
+fn limit_true_peak(buffer: &mut AudioBuffer, ceiling: f32) -> f32 {
+    let rate = buffer.sample_rate as f32;
+    let lookahead = ((rate * 0.0015) as usize).max(1);
+    let release = 1.0 - (-1.0 / (rate * 0.05)).exp();
+
+    let required: Vec<f32> = frame_peaks(buffer)
+        .into_iter()
+        .map(|peak| if peak > ceiling { ceiling / peak } else { 1.0 })
+        .collect();
+    if required.iter().all(|&g| g >= 1.0) {
+        return 1.0;
+    }
+
+    // Sliding minimum over required[n..=n + lookahead].
+    let frames = required.len();
+    let mut window: VecDeque<usize> = VecDeque::new();
+    let mut held = vec![1.0f32; frames];
+    for idx in (0..frames).rev() {
+        while window.back().is_some_and(|&j| required[j] >= required[idx]) {
+            window.pop_back();
+        }
+        window.push_back(idx);
+        while window.front().is_some_and(|&j| j > idx + lookahead) {
+            window.pop_front();
+        }
+        held[idx] = required[window[0]];
+    }
+
+    // Start as if the first window's gain had been held before the buffer, so
+    // peaks in the first lookahead window are already covered.
+    let mut smoothed = held[0];
+    let mut history: VecDeque<f32> = std::iter::repeat_n(smoothed, lookahead + 1).collect();
+    let mut sum = smoothed * (lookahead + 1) as f32;
+    let mut lowest = 1.0f32;
+    for (frame, &target) in buffer.samples.chunks_exact_mut(2).zip(&held) {
+        smoothed = if target < smoothed {
+            target
+        } else {
+            smoothed + (target - smoothed) * release
+        };
+        history.push_back(smoothed);
+        sum += smoothed - history.pop_front().unwrap_or(0.0);
+        let gain = (sum / (lookahead + 1) as f32).min(1.0);
+        lowest = lowest.min(gain);
+        frame[0] *= gain;
+        frame[1] *= gain;
+    }
+    lowest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(sample_rate: u32, freq: f32, amplitude: f32, seconds: f32) -> AudioBuffer {
+        let frames = (sample_rate as f32 * seconds) as usize;
+        let mono: Vec<f32> = (0..frames)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * freq * i as f32 / sample_rate as f32).sin())
+            .collect();
+        AudioBuffer::from_interleaved(sample_rate, 1, &mono).unwrap()
+    }
+
+    #[test]
+    fn measures_reference_tone() {
+        // A 1 kHz sine peaking at -23 dBFS in both channels reads -23 LUFS:
+        // -26 dB mean square per channel, +3 dB for two channels, and the
+        // K-weighting gain at 1 kHz cancels the -0.691 offset.
+        let tone = sine(48_000, 1000.0, db_to_gain(-23.0), 5.0);
+        let loudness = measure_loudness(&tone);
+        assert!((loudness.integrated_lufs - (-23.0)).abs() < 0.1, "{}", loudness.integrated_lufs);
+        assert!((loudness.true_peak_dbtp - (-23.0)).abs() < 0.1);
+    }
+
+    #[test]
+    fn silence_has_no_integrated_loudness() {
+        let silence = AudioBuffer::silence(48_000, 48_000);
+        assert_eq!(integrated_loudness(&silence), f32::NEG_INFINITY);
+    }
+
+    #[test]
+    fn normalizes_and_limits_true_peak() {
+        let mut tone = sine(48_000, 997.0, 0.05, 3.0);
+        let report = normalize_loudness(&mut tone, &LoudnessTarget::new(-1.0, -3.0));
+        assert!(report.gain_db > 10.0);
+        assert!(report.limiter_reduction_db > 0.0);
+        assert!(report.output.true_peak_dbtp <= -2.9, "{}", report.output.true_peak_dbtp);
+
+        let mut quiet = sine(48_000, 997.0, 0.5, 3.0);
+        let report = normalize_loudness(&mut quiet, &LoudnessTarget::BROADCAST);
+        assert_eq!(report.limiter_reduction_db, 0.0);
+        assert!((report.output.integrated_lufs - (-23.0)).abs() < 0.1);
+    }
+}
@@ -0,0 +1,756 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use crate::audio::decode::decode_file;
+use crate::audio::ducking::{DuckKey, Ducking, DuckingCurve};
+use crate::audio::envelope::place_music;
+use crate::audio::loudness::{normalize_loudness, LoudnessReport, LoudnessTarget};
+use crate::audio::{DialogueClip, MusicTrack, SfxEvent};
+use crate::scene::Track;
+
+pub const DEFAULT_SAMPLE_RATE: u32 = 48_000;
+
+// Interleaved stereo f32 PCM. Everything the mixer touches is stereo; decoders
+// up/down-mix on load.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioBuffer {
+    pub sample_rate: u32,
+    pub samples: Vec<f32>,
+}
+
+impl AudioBuffer {
+    pub const CHANNELS: u16 = 2;
+
+    pub fn silence(sample_rate: u32, frames: usize) -> Self {
+        Self {
+            sample_rate,
+            samples: vec![0.0; frames * 2],
+        }
+    }
+
+    // Mono is duplicated to both sides; extra channels beyond the first two are dropped.
+    pub fn from_interleaved(sample_rate: u32, channels: usize, samples: &[f32]) -> Result<Self> {
+        let stereo = match channels {
+            0 => bail!("interleaved audio needs at least one channel"),
+            2 => samples.to_vec(),
+            1 => samples.iter().flat_map(|&s| [s, s]).collect(),
+            _ => samples
+                .chunks_exact(channels)
+                .flat_map(|frame| [frame[0], frame[1]])
+                .collect(),
+        };
+        Ok(Self {
+            sample_rate,
+            samples: stereo,
+        })
+    }
+
+    pub fn frames(&self) -> usize {
+        self.samples.len() / 2
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.frames() as f32 / self.sample_rate as f32
+    }
+
+    pub fn frame(&self, idx: usize) -> [f32; 2] {
+        [self.samples[idx * 2], self.samples[idx * 2 + 1]]
+    }
+
+    // Linear-interpolation resampling. Cheap and deterministic; good enough
+    // for SFX and music that are already at a common rate.
+    pub fn resampled(&self, sample_rate: u32) -> AudioBuffer {
+        if sample_rate == self.sample_rate || self.frames() == 0 {
+            return AudioBuffer {
+                sample_rate,
+                samples: self.samples.clone(),
+            };
+        }
+        AudioBuffer {
+            sample_rate,
+            samples: self.stretch(self.sample_rate as f64 / sample_rate as f64),
+        }
+    }
+
+    // Plays back `rate` times faster at the same sample rate, so pitch and
+    // duration change together like a tape.
+    pub fn varispeed(&self, rate: f32) -> AudioBuffer {
+        if rate == 1.0 || rate <= 0.0 || self.frames() == 0 {
+            return self.clone();
+        }
+        AudioBuffer {
+            sample_rate: self.sample_rate,
+            samples: self.stretch(rate as f64),
+        }
+    }
+
+    pub fn reversed(&self) -> AudioBuffer {
+        AudioBuffer {
+            sample_rate: self.sample_rate,
+            samples: self
+                .samples
+                .chunks_exact(2)
+                .rev()
+                .flatten()
+                .copied()
+                .collect(),
+        }
+    }
+
+    // Reads the frames at positions 0, step, 2 * step, ... with linear interpolation.
+    fn stretch(&self, step: f64) -> Vec<f32> {
+        let frames = (self.frames() as f64 / step).round() as usize;
+        let last = self.frames() - 1;
+        let mut samples = Vec::with_capacity(frames * 2);
+        for idx in 0..frames {
+            let pos = idx as f64 * step;
+            let i0 = (pos.floor() as usize).min(last);
+            let i1 = (i0 + 1).min(last);
+            let frac = (pos - i0 as f64) as f32;
+            let a = self.frame(i0);
+            let b = self.frame(i1);
+            samples.push(a[0] + (b[0] - a[0]) * frac);
+            samples.push(a[1] + (b[1] - a[1]) * frac);
+        }
+        samples
+    }
+}
+
+// Sums sources into a fixed-length stereo buffer. Placement is rounded to the
+// nearest sample, so the same inputs always produce the same output.
+pub struct Mixer {
+    sample_rate: u32,
+    output: AudioBuffer,
+}
+
+impl Mixer {
+    pub fn new(duration: f32, sample_rate: u32) -> Result<Self> {
+        if duration <= 0.0 {
+            bail!("mix duration must be > 0");
+        }
+        if sample_rate == 0 {
+            bail!("sample rate must be > 0");
+        }
+        let frames = (duration as f64 * sample_rate as f64).round() as usize;
+        Ok(Self {
+            sample_rate,
+            output: AudioBuffer::silence(sample_rate, frames),
+        })
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn frames(&self) -> usize {
+        self.output.frames()
+    }
+
+    pub fn time_to_frame(&self, time: f32) -> usize {
+        (time.max(0.0) as f64 * self.sample_rate as f64).round() as usize
+    }
+
+    // Plays `source` once starting at `time`; anything past the end is cut.
+    pub fn add(&mut self, source: &AudioBuffer, time: f32, volume: f32) {
+        self.add_with_gain(source, time, |_| volume);
+    }
+
+    // Like `add`, with a per-sample gain taken from `gain(seconds since time)`.
+    pub fn add_with_gain(&mut self, source: &AudioBuffer, time: f32, gain: impl FnMut(f32) -> f32) {
+        let end = self.frames() as f32 / self.sample_rate as f32;
+        self.add_segment_with_gain(source, time, end, 0.0, false, gain);
+    }
+
+    // Repeats `source` back to back from `time` until the end of the mix.
+    pub fn add_looped(&mut self, source: &AudioBuffer, time: f32, volume: f32) {
+        let end = self.frames() as f32 / self.sample_rate as f32;
+        self.add_segment(source, time, end, 0.0, volume, true);
+    }
+
+    // Plays `source` on the output from `start` to `end` seconds, beginning
+    // `offset` seconds into the source. Looped sources wrap to their first
+    // sample; others stop when they run out.
+    pub fn add_segment(
+        &mut self,
+        source: &AudioBuffer,
+        start: f32,
+        end: f32,
+        offset: f32,
+        volume: f32,
+        looped: bool,
+    ) {
+        self.add_segment_with_gain(source, start, end, offset, looped, |_| volume);
+    }
+
+    // Like `add_segment`, with a per-sample gain taken from
+    // `gain(seconds since start)`. Fades and automation go through here.
+    pub fn add_segment_with_gain(
+        &mut self,
+        source: &AudioBuffer,
+        start: f32,
+        end: f32,
+        offset: f32,
+        looped: bool,
+        mut gain: impl FnMut(f32) -> f32,
+    ) {
+        let source = self.conform(source);
+        let len = source.frames();
+        if len == 0 {
+            return;
+        }
+        let first = self.time_to_frame(start);
+        let out_end = self.time_to_frame(end).min(self.frames());
+        let mut out = first;
+        let mut pos = self.time_to_frame(offset);
+        if looped {
+            pos %= len;
+        }
+
+        let rate = self.sample_rate as f64;
+        let mut local_gain = |frame: usize| gain(((frame - first) as f64 / rate) as f32);
+        while out < out_end && pos < len {
+            let count = (out_end - out).min(len - pos);
+            self.add_frames(&source, out, pos, pos + count, &mut local_gain);
+            out += count;
+            pos += count;
+            if pos == len && looped {
+                pos = 0;
+            }
+        }
+    }
+
+    pub fn finish(self) -> AudioBuffer {
+        self.output
+    }
+
+    fn conform<'a>(&self, source: &'a AudioBuffer) -> Cow<'a, AudioBuffer> {
+        if source.sample_rate == self.sample_rate {
+            Cow::Borrowed(source)
+        } else {
+            Cow::Owned(source.resampled(self.sample_rate))
+        }
+    }
+
+    // Adds source frames `from..to` at output frame `start`.
+    // `gain` is called with each output frame index.
+    fn add_frames(
+        &mut self,
+        source: &AudioBuffer,
+        start: usize,
+        from: usize,
+        to: usize,
+        mut gain: impl FnMut(usize) -> f32,
+    ) {
+        let available = self.frames().saturating_sub(start);
+        let count = to.saturating_sub(from).min(available);
+        let out = &mut self.output.samples[start * 2..(start + count) * 2];
+        let src = &source.samples[from * 2..(from + count) * 2];
+        for (idx, (dst, sample)) in out.chunks_exact_mut(2).zip(src.chunks_exact(2)).enumerate() {
+            let g = gain(start + idx);
+            dst[0] += sample[0] * g;
+            dst[1] += sample[1] * g;
+        }
+    }
+}
+
+// Decoded sources keyed by path. Each distinct file is decoded once, however
+// many events reference it.
+pub struct SoundBank {
+    sample_rate: u32,
+    sounds: HashMap<PathBuf, AudioBuffer>,
+}
+
+impl SoundBank {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            sounds: HashMap::new(),
+        }
+    }
+
+    pub fn load(&mut self, path: &Path) -> Result<&AudioBuffer> {
+        if !self.sounds.contains_key(path) {
+            let decoded = decode_file(path, self.sample_rate)?;
+            self.sounds.insert(path.to_path_buf(), decoded);
+        }
+        Ok(&self.sounds[path])
+    }
+
+    // Loads every file the events reference, so missing assets fail before mixing.
+    pub fn load_events(&mut self, events: &[SfxEvent]) -> Result<()> {
+        for event in events {
+            self.load(&event.path)
+                .with_context(|| format!("sfx at {:.3}s", event.time))?;
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&AudioBuffer> {
+        self.sounds.get(path)
+    }
+
+    pub fn len(&self) -> usize {
+        self.sounds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sounds.is_empty()
+    }
+}
+
+// Mixes music tracks plus SFX events for a timeline of `duration` seconds.
+// Every event plays its own `path`; tracks may overlap.
+pub fn mix_timeline_audio(
+    music: &[MusicTrack],
+    sfx: &[SfxEvent],
+    duration: f32,
+    sample_rate: u32,
+) -> Result<AudioBuffer> {
+    let mix = mix_timeline(music, &[], sfx, &MixOptions::default(), duration, sample_rate)?;
+    Ok(mix.audio)
+}
+
+// Whole-mix processing on top of the per-track settings.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MixOptions {
+    pub ducking: Option<Ducking>,
+    // Normalizes and true-peak limits the final mix.
+    pub loudness: Option<LoudnessTarget>,
+}
+
+pub struct TimelineMix {
+    pub audio: AudioBuffer,
+    // The music gain that ducking applied, when enabled.
+    pub ducking: Option<DuckingCurve>,
+    // Before/after measurements, when loudness normalization is enabled.
+    pub loudness: Option<LoudnessReport>,
+}
+
+// Like `mix_timeline_audio`, plus dialogue. Music, dialogue and SFX are mixed
+// on separate buses so `options` can process one against another before they
+// are summed.
+pub fn mix_timeline(
+    music: &[MusicTrack],
+    dialogue: &[DialogueClip],
+    sfx: &[SfxEvent],
+    options: &MixOptions,
+    duration: f32,
+    sample_rate: u32,
+) -> Result<TimelineMix> {
+    let mut music_bus = Mixer::new(duration, sample_rate)?;
+    let mut dialogue_bus = Mixer::new(duration, sample_rate)?;
+    let mut sfx_bus = Mixer::new(duration, sample_rate)?;
+    let mut bank = SoundBank::new(sample_rate);
+
+    let placements = place_music(music, duration);
+    for (idx, (track, placement)) in music.iter().zip(&placements).enumerate() {
+        let source = bank
+            .load(&track.path)
+            .with_context(|| format!("music track {idx}"))?;
+        let envelope = placement
+            .envelope()
+            .with_context(|| format!("music track {idx}"))?;
+        let mut fades = envelope.cursor();
+        let mut automation = track.automation.as_ref().map(Track::cursor);
+        music_bus.add_segment_with_gain(
+            source,
+            placement.start,
+            placement.end,
+            track.source_offset,
+            track.looped,
+            |t| {
+                let automated = automation.as_mut().map_or(1.0, |curve| curve.sample(t));
+                track.volume * fades.sample(t) * automated
+            },
+        );
+    }
+
+    for (idx, clip) in dialogue.iter().enumerate() {
+        let source = bank
+            .load(&clip.path)
+            .with_context(|| format!("dialogue clip {idx}"))?;
+        let end = clip
+            .duration()
+            .map_or(duration, |length| clip.start_time + length);
+        dialogue_bus.add_segment(
+            source,
+            clip.start_time,
+            end,
+            clip.source_start(),
+            clip.gain,
+            false,
+        );
+    }
+
+    bank.load_events(sfx)?;
+    mix_sfx(&mut sfx_bus, &bank, sfx)?;
+
+    let mut music_out = music_bus.finish();
+    let dialogue_out = dialogue_bus.finish();
+    let sfx_out = sfx_bus.finish();
+    let ducking = match &options.ducking {
+        Some(ducking) => {
+            let curve = match ducking.key {
+                DuckKey::Sfx => ducking.curve(&sfx_out),
+                DuckKey::Dialogue => ducking.curve(&dialogue_out),
+                DuckKey::All => {
+                    let mut key = Mixer::new(duration, sample_rate)?;
+                    key.add(&dialogue_out, 0.0, 1.0);
+                    key.add(&sfx_out, 0.0, 1.0);
+                    ducking.curve(&key.finish())
+                }
+            };
+            curve.apply(&mut music_out);
+            Some(curve)
+        }
+        None => None,
+    };
+
+    let mut mixer = Mixer::new(duration, sample_rate)?;
+    mixer.add(&music_out, 0.0, 1.0);
+    mixer.add(&dialogue_out, 0.0, 1.0);
+    mixer.add(&sfx_out, 0.0, 1.0);
+    let mut audio = mixer.finish();
+    let loudness = options
+        .loudness
+        .map(|target| normalize_loudness(&mut audio, &target));
+    Ok(TimelineMix {
+        audio,
+        ducking,
+        loudness,
+    })
+}
+
+pub fn mix_sfx(mixer: &mut Mixer, bank: &SoundBank, sfx: &[SfxEvent]) -> Result<()> {
+    for event in sfx {
+        let source = bank
+            .get(&event.path)
+            .with_context(|| format!("sfx not loaded: {}", event.path.display()))?;
+        let source = shape_sfx(source, event, mixer.sample_rate());
+        let gain = event.gain();
+        match &event.automation {
+            Some(curve) => {
+                let mut curve = curve.cursor();
+                mixer.add_with_gain(&source, event.time, |t| gain * curve.sample(t));
+            }
+            None => mixer.add(&source, event.time, gain),
+        }
+    }
+    Ok(())
+}
+
+// Applies the event's playback rate and direction. The source is conformed to
+// the mix rate first so the rate is relative to normal playback.
+fn shape_sfx<'a>(source: &'a AudioBuffer, event: &SfxEvent, sample_rate: u32) -> Cow<'a, AudioBuffer> {
+    let rate = event.playback_rate();
+    if rate == 1.0 && !event.reverse {
+        return Cow::Borrowed(source);
+    }
+    let mut shaped = source.resampled(sample_rate).varispeed(rate);
+    if event.reverse {
+        shaped = shaped.reversed();
+    }
+    Cow::Owned(shaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::envelope::{fade_envelope, Fade};
+    use crate::audio::SfxVariation;
+    use crate::audio::wav::wav_bytes;
+    use crate::scene::{Easing, Keyframe};
+
+    fn mono(sample_rate: u32, samples: &[f32]) -> AudioBuffer {
+        AudioBuffer::from_interleaved(sample_rate, 1, samples).unwrap()
+    }
+
+    fn left(buffer: &AudioBuffer) -> Vec<f32> {
+        buffer.samples.iter().step_by(2).copied().collect()
+    }
+
+    #[test]
+    fn places_events_on_exact_samples() {
+        let mut mixer = Mixer::new(1.0, 10).unwrap();
+        let click = mono(10, &[1.0, 0.5]);
+        mixer.add(&click, 0.3, 1.0);
+        mixer.add(&click, 0.85, 0.5);
+        let out = mixer.finish();
+        assert_eq!(
+            left(&out),
+            vec![0.0, 0.0, 0.0, 1.0, 0.5, 0.0, 0.0, 0.0, 0.0, 0.5]
+        );
+    }
+
+    #[test]
+    fn loops_music_and_sums_with_sfx() {
+        let mut mixer = Mixer::new(0.7, 10).unwrap();
+        mixer.add_looped(&mono(10, &[0.1, 0.2, 0.3]), 0.0, 1.0);
+        mixer.add(&mono(10, &[0.5]), 0.4, 1.0);
+        let out = mixer.finish();
+        let expected = [0.1, 0.2, 0.3, 0.1, 0.7, 0.3, 0.1];
+        for (got, want) in left(&out).iter().zip(expected) {
+            assert!((got - want).abs() < 1e-6, "{got} != {want}");
+        }
+        assert_eq!(out.frames(), 7);
+    }
+
+    #[test]
+    fn music_segments_honor_start_end_and_offset() {
+        let song = mono(10, &[0.1, 0.2, 0.3, 0.4]);
+        let mut mixer = Mixer::new(1.0, 10).unwrap();
+        // Starts at 0.2s, 0.1s into the file, no loop: runs out after 3 samples.
+        mixer.add_segment(&song, 0.2, 1.0, 0.1, 1.0, false);
+        // Looped from 0.6s and cut at 0.9s.
+        mixer.add_segment(&song, 0.6, 0.9, 0.3, 1.0, true);
+        let out = mixer.finish();
+        let expected = [0.0, 0.0, 0.2, 0.3, 0.4, 0.0, 0.4, 0.1, 0.2, 0.0];
+        for (got, want) in left(&out).iter().zip(expected) {
+            assert!((got - want).abs() < 1e-6, "{got} != {want}");
+        }
+    }
+
+    #[test]
+    fn interleaved_input_becomes_stereo() {
+        let mono = AudioBuffer::from_interleaved(10, 1, &[0.1, 0.2]).unwrap();
+        assert_eq!(mono.samples, [0.1, 0.1, 0.2, 0.2]);
+        let surround = AudioBuffer::from_interleaved(10, 3, &[0.1, 0.2, 0.3, 0.4, 0.5, 0.6]).unwrap();
+        assert_eq!(surround.samples, [0.1, 0.2, 0.4, 0.5]);
+        assert!(AudioBuffer::from_interleaved(10, 0, &[0.1]).is_err());
+    }
+
+    #[test]
+    fn resamples_linearly() {
+        let source = mono(2, &[0.0, 1.0]);
+        assert_eq!(left(&source.resampled(4)), vec![0.0, 0.5, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn mixing_is_deterministic() {
+        let render = || {
+            let mut mixer = Mixer::new(0.5, 100).unwrap();
+            mixer.add_looped(&mono(100, &[0.25, -0.25, 0.125]), 0.0, 0.8);
+            mixer.add(&mono(100, &[0.9, 0.9]), 0.123, 0.7);
+            mixer.finish()
+        };
+        let out = render();
+        assert_eq!(wav_bytes(&out).unwrap(), wav_bytes(&render()).unwrap());
+
+        // The loop wraps every 3 frames at 0.8 gain. The 0.123 s offset rounds
+        // to frame 12, where the second source adds 0.9 * 0.7 for two frames.
+        let mut expected: Vec<f32> = (0..50).map(|i| [0.2, -0.2, 0.1][i % 3]).collect();
+        expected[12] += 0.63;
+        expected[13] += 0.63;
+        assert_eq!(out.frames(), expected.len());
+        for (i, want) in expected.into_iter().enumerate() {
+            let (l, r) = (out.samples[i * 2], out.samples[i * 2 + 1]);
+            assert!((l - want).abs() < 1e-6 && l == r, "frame {i}: {l}/{r} != {want}");
+        }
+    }
+
+    #[test]
+    fn routes_each_event_to_its_own_file() {
+        let dir = std::env::temp_dir().join(format!("s2s-mixer-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let bounce = dir.join("bounce.wav");
+        let click = dir.join("click.wav");
+        crate::audio::write_wav(&mono(10, &[0.5]), &bounce).unwrap();
+        crate::audio::write_wav(&mono(10, &[-0.25, -0.25]), &click).unwrap();
+
+        let event = |path: &Path, time| SfxEvent::new(path, time, 1.0);
+        let events = [event(&bounce, 0.1), event(&click, 0.4), event(&bounce, 0.7)];
+        let mut bank = SoundBank::new(10);
+        bank.load_events(&events).unwrap();
+        assert_eq!(bank.len(), 2);
+
+        let out = mix_timeline_audio(&[], &events, 1.0, 10).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+        let expected = [0.0, 0.5, 0.0, 0.0, -0.25, -0.25, 0.0, 0.5, 0.0, 0.0];
+        for (got, want) in left(&out).iter().zip(expected) {
+            assert!((got - want).abs() < 1e-3, "{got} != {want}");
+        }
+    }
+
+    fn track(start: f32, end: f32, crossfade: f32) -> MusicTrack {
+        MusicTrack {
+            path: PathBuf::from("song.wav"),
+            start,
+            end,
+            looped: true,
+            volume: 1.0,
+            source_offset: 0.0,
+            fade_in: 0.0,
+            fade_out: 0.0,
+            crossfade,
+            automation: None,
+        }
+    }
+
+    #[test]
+    fn fades_ramp_from_and_to_silence() {
+        let envelope = fade_envelope(1.0, Fade::linear(0.2), Fade::linear(0.4)).unwrap();
+        let mut mixer = Mixer::new(1.0, 10).unwrap();
+        let mut cursor = envelope.cursor();
+        mixer.add_segment_with_gain(&mono(10, &[1.0]), 0.0, 1.0, 0.0, true, |t| cursor.sample(t));
+        let out = mixer.finish();
+        let expected = [0.0, 0.5, 1.0, 1.0, 1.0, 1.0, 1.0, 0.75, 0.5, 0.25];
+        for (got, want) in left(&out).iter().zip(expected) {
+            assert!((got - want).abs() < 1e-5, "{got} != {want}");
+        }
+    }
+
+    #[test]
+    fn crossfades_extend_the_previous_track_with_equal_power() {
+        // Listed out of order; the crossfade pairs tracks by start time.
+        let tracks = [track(4.0, 8.0, 1.0), track(0.0, 4.0, 0.0)];
+        let placements = place_music(&tracks, 8.0);
+        assert_eq!(placements[1].end, 5.0);
+        assert_eq!(placements[1].fade_out.duration, 1.0);
+        assert_eq!(placements[0].fade_in.duration, 1.0);
+
+        let outgoing = placements[1].envelope().unwrap();
+        let incoming = placements[0].envelope().unwrap();
+        for step in 0..=10 {
+            let t = 4.0 + step as f32 * 0.1;
+            let a = outgoing.sample(t - placements[1].start);
+            let b = incoming.sample(t - placements[0].start);
+            assert!((a * a + b * b - 1.0).abs() < 1e-4, "power at {t}: {}", a * a + b * b);
+        }
+    }
+
+    #[test]
+    fn sfx_automation_uses_track_easing() {
+        let curve = Track::new(vec![
+            Keyframe::new(0.0, 1.0, Easing::Hold),
+            Keyframe::new(0.2, 0.25, Easing::Linear),
+        ])
+        .unwrap();
+        let mut mixer = Mixer::new(0.6, 10).unwrap();
+        let mut cursor = curve.cursor();
+        mixer.add_with_gain(&mono(10, &[1.0; 4]), 0.1, |t| 0.5 * cursor.sample(t));
+        let out = mixer.finish();
+        let expected = [0.0, 0.5, 0.5, 0.125, 0.125, 0.0];
+        for (got, want) in left(&out).iter().zip(expected) {
+            assert!((got - want).abs() < 1e-6, "{got} != {want}");
+        }
+    }
+
+    #[test]
+    fn dialogue_plays_its_trimmed_range() {
+        let dir = std::env::temp_dir().join(format!("s2s-dialogue-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let line = dir.join("line.wav");
+        crate::audio::write_wav(&mono(10, &[0.1, 0.2, 0.3, 0.4, 0.5]), &line).unwrap();
+
+        let trimmed = DialogueClip::new(&line, 0.2, Some(0.1), Some(0.4), 0.5).unwrap();
+        let untrimmed = DialogueClip::new(&line, 0.8, None, None, 1.0).unwrap();
+        let mix = mix_timeline(
+            &[],
+            &[trimmed, untrimmed],
+            &[],
+            &MixOptions::default(),
+            1.0,
+            10,
+        )
+        .unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+        let expected = [0.0, 0.0, 0.1, 0.15, 0.2, 0.0, 0.0, 0.0, 0.1, 0.2];
+        for (got, want) in left(&mix.audio).iter().zip(expected) {
+            assert!((got - want).abs() < 1e-3, "{got} != {want}");
+        }
+    }
+
+    #[test]
+    fn ducking_without_a_key_follows_dialogue() {
+        let dir = std::env::temp_dir().join(format!("s2s-duck-dialogue-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let song = dir.join("song.wav");
+        let line = dir.join("line.wav");
+        crate::audio::write_wav(&mono(100, &[0.5]), &song).unwrap();
+        crate::audio::write_wav(&mono(100, &[0.25; 20]), &line).unwrap();
+
+        let music = MusicTrack {
+            path: song,
+            ..track(0.0, 1.0, 0.0)
+        };
+        let clip = DialogueClip::new(&line, 0.4, None, None, 1.0).unwrap();
+        // As a project would give it: no `key`, and no SFX at all.
+        let ducking: Ducking =
+            serde_json::from_str(r#"{ "amount_db": 20.0, "attack": 0.0, "release": 0.0 }"#).unwrap();
+        let options = MixOptions {
+            ducking: Some(ducking),
+            loudness: None,
+        };
+        let mix = mix_timeline(&[music], &[clip], &[], &options, 1.0, 100).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        let curve = mix.ducking.unwrap();
+        assert!(curve.gains.iter().any(|&gain| (gain - 0.1).abs() < 1e-3));
+        let out = left(&mix.audio);
+        assert!((out[20] - 0.5).abs() < 1e-3, "{}", out[20]);
+        // Music at a tenth under the line, plus the line itself.
+        assert!((out[50] - (0.05 + 0.25)).abs() < 1e-3, "{}", out[50]);
+        assert!((out[80] - 0.5).abs() < 1e-3, "{}", out[80]);
+    }
+
+    #[test]
+    fn varispeed_and_reverse_reshape_the_source() {
+        let ramp = mono(10, &[0.0, 0.1, 0.2, 0.3]);
+        assert_eq!(left(&ramp.reversed()), vec![0.3, 0.2, 0.1, 0.0]);
+        let fast = left(&ramp.varispeed(2.0));
+        assert_eq!(fast.len(), 2);
+        assert!((fast[1] - 0.2).abs() < 1e-6);
+        let slow = left(&ramp.varispeed(0.5));
+        assert_eq!(slow.len(), 8);
+        assert!((slow[1] - 0.05).abs() < 1e-6);
+
+        let event = SfxEvent {
+            rate: 2.0,
+            ..SfxEvent::new("click.wav", 0.0, 1.0)
+        };
+        assert_eq!(event.playback_rate(), 2.0);
+    }
+
+    #[test]
+    fn sfx_variation_is_seeded_and_per_event() {
+        let varied = |time, seed| SfxEvent {
+            variation: Some(SfxVariation {
+                seed,
+                pitch: 2.0,
+                volume_db: 3.0,
+            }),
+            ..SfxEvent::new("click.wav", time, 0.5)
+        };
+        let rates: Vec<f32> = (0..8).map(|i| varied(i as f32 * 0.5, 42).playback_rate()).collect();
+        let again: Vec<f32> = (0..8).map(|i| varied(i as f32 * 0.5, 42).playback_rate()).collect();
+        assert_eq!(rates, again);
+        assert!(rates.windows(2).any(|pair| pair[0] != pair[1]));
+        assert_ne!(varied(1.0, 42).playback_rate(), varied(1.0, 43).playback_rate());
+        for event in (0..32).map(|i| varied(i as f32 * 0.1, 7)) {
+            let rate = event.playback_rate();
+            assert!((2f32.powf(-2.0 / 12.0)..=2f32.powf(2.0 / 12.0)).contains(&rate));
+            let gain = event.gain();
+            assert!((0.5 * 10f32.powf(-3.0 / 20.0)..=0.5 * 10f32.powf(3.0 / 20.0)).contains(&gain));
+        }
+    }
+
+    #[test]
+    fn wav_header_and_clipping() {
+        let buffer = AudioBuffer {
+            sample_rate: 8000,
+            samples: vec![0.0, 1.5, -2.0, 0.5],
+        };
+        let bytes = wav_bytes(&buffer).unwrap();
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes(bytes[24..28].try_into().unwrap()), 8000);
+        assert_eq!(u32::from_le_bytes(bytes[40..44].try_into().unwrap()), 8);
+        let samples: Vec<i16> = bytes[44..]
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect();
+        assert_eq!(samples, vec![0, i16::MAX, -i16::MAX, 16384]);
+    }
+}
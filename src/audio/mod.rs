@@ -3,9 +3,32 @@ use std::path::Path;
 
 use anyhow::{bail, Context, Result};
 
+pub mod analysis;
+pub mod decode;
+pub mod ducking;
+pub mod envelope;
+pub mod loudness;
+pub mod mixer;
+pub mod preview;
 pub mod timeline;
-
-pub use timeline::{MusicTrack, SfxEvent};
+pub mod wav;
+
+pub use analysis::{
+    analyze, analyze_music, markers, pulse_track, AudioAnalysis, BeatGrid, Onset, RmsEnvelope,
+};
+pub use decode::decode_file;
+pub use ducking::{db_to_gain, DuckKey, Ducking, DuckingCurve};
+pub use envelope::{fade_envelope, place_music, Fade, MusicPlacement};
+pub use loudness::{
+    measure_loudness, normalize_loudness, Loudness, LoudnessReport, LoudnessTarget,
+};
+pub use mixer::{
+    mix_sfx, mix_timeline, mix_timeline_audio, AudioBuffer, MixOptions, Mixer, SoundBank,
+    TimelineMix, DEFAULT_SAMPLE_RATE,
+};
+pub use preview::PreviewAudio;
+pub use timeline::{DialogueClip, MusicTrack, SfxEvent, SfxVariation};
+pub use wav::write_wav;
 
 pub struct AudioEngine {
     music: raylib::ffi::Music,
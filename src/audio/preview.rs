@@ -0,0 +1,423 @@
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use crate::audio::envelope::place_music;
+use crate::audio::{DialogueClip, MusicTrack, SfxEvent};
+use crate::scene::Track;
+
+// Time jumps larger than this between updates are treated as a seek.
+const SEEK_THRESHOLD: f32 = 0.25;
+// Streams further than this from timeline time are re-seeked.
+const MAX_DRIFT: f32 = 0.08;
+// Overlapping plays per SFX file.
+const VOICES: usize = 4;
+
+// Realtime playback of the same music, dialogue and SFX lists the offline
+// mixer takes, driven by preview time. Call `update(t)` once per preview
+// frame: SFX fire as `t` crosses them, and streams follow `t` across seeks,
+// restarts and slow frames. Fades, crossfades and automation match the offline
+// mix, as do SFX rate and variation; reverse playback, ducking and
+// loudness normalization are offline only.
+pub struct PreviewAudio {
+    streams: Vec<PreviewStream>,
+    sounds: HashMap<PathBuf, Vec<raylib::ffi::Sound>>,
+    events: Vec<SfxEvent>,
+    next_event: usize,
+    next_voice: usize,
+    last_time: Option<f32>,
+    paused: bool,
+    // Whether `new` initialized the audio device, and so closes it on drop.
+    owns_device: bool,
+}
+
+struct PreviewStream {
+    music: raylib::ffi::Music,
+    span: StreamSpan,
+    volume: f32,
+    envelope: Track<f32>,
+    automation: Option<Track<f32>>,
+    playing: bool,
+}
+
+impl PreviewAudio {
+    pub fn new(music: &[MusicTrack], dialogue: &[DialogueClip], sfx: &[SfxEvent]) -> Result<Self> {
+        let owns_device = unsafe { !raylib::ffi::IsAudioDeviceReady() };
+        if owns_device {
+            unsafe { raylib::ffi::InitAudioDevice() };
+        }
+        if unsafe { !raylib::ffi::IsAudioDeviceReady() } {
+            bail!("raylib audio device not ready");
+        }
+
+        let mut audio = Self {
+            streams: Vec::new(),
+            sounds: HashMap::new(),
+            events: Vec::new(),
+            next_event: 0,
+            next_voice: 0,
+            last_time: None,
+            paused: false,
+            owns_device,
+        };
+
+        let placements = place_music(music, f32::INFINITY);
+        for (idx, (track, placement)) in music.iter().zip(&placements).enumerate() {
+            let loaded =
+                PreviewStream::load(&track.path).with_context(|| format!("music track {idx}"))?;
+            let stream = PreviewStream {
+                span: StreamSpan {
+                    start: placement.start,
+                    end: placement.end,
+                    offset: track.source_offset,
+                    looped: track.looped,
+                    ..loaded.span
+                },
+                volume: track.volume,
+                envelope: placement.envelope()?,
+                automation: track.automation.clone(),
+                ..loaded
+            };
+            audio.streams.push(stream);
+        }
+
+        for (idx, clip) in dialogue.iter().enumerate() {
+            let loaded =
+                PreviewStream::load(&clip.path).with_context(|| format!("dialogue clip {idx}"))?;
+            let stream = PreviewStream {
+                span: StreamSpan {
+                    start: clip.start_time,
+                    end: clip
+                        .duration()
+                        .map_or(f32::INFINITY, |length| clip.start_time + length),
+                    offset: clip.source_start(),
+                    ..loaded.span
+                },
+                volume: clip.gain,
+                ..loaded
+            };
+            audio.streams.push(stream);
+        }
+
+        for event in sfx {
+            if !audio.sounds.contains_key(&event.path) {
+                let voices = load_voices(&event.path)
+                    .with_context(|| format!("sfx at {:.3}s", event.time))?;
+                audio.sounds.insert(event.path.clone(), voices);
+            }
+        }
+        audio.events = sfx.to_vec();
+        audio.events.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        Ok(audio)
+    }
+
+    // Advances to timeline time `t`. Backwards or large jumps re-seek every
+    // stream and skip the SFX in between instead of firing them all at once.
+    pub fn update(&mut self, t: f32) {
+        if is_jump(self.last_time, t) {
+            self.seek(t);
+        }
+        self.last_time = Some(t);
+
+        while let Some(event) = self.events.get(self.next_event) {
+            if event.time > t {
+                break;
+            }
+            if let Some(voices) = self.sounds.get(&event.path) {
+                let voice = voices[self.next_voice % voices.len()];
+                self.next_voice += 1;
+                unsafe {
+                    raylib::ffi::SetSoundVolume(voice, event.gain());
+                    raylib::ffi::SetSoundPitch(voice, event.playback_rate());
+                    raylib::ffi::PlaySound(voice);
+                }
+            }
+            self.next_event += 1;
+        }
+
+        // A jump has already re-seeked the streams.
+        for stream in &mut self.streams {
+            stream.sync(t, false);
+        }
+    }
+
+    // Jumps to `t` without firing the SFX in between. The next `update`
+    // continues from here. While paused, streams stay silent until then.
+    pub fn seek(&mut self, t: f32) {
+        self.stop_sounds();
+        self.next_event = self.events.partition_point(|event| event.time < t);
+        if self.paused {
+            for stream in &mut self.streams {
+                stream.stop();
+            }
+            self.last_time = None;
+            return;
+        }
+        self.last_time = Some(t);
+        for stream in &mut self.streams {
+            stream.sync(t, true);
+        }
+    }
+
+    pub fn pause(&mut self) {
+        if self.paused {
+            return;
+        }
+        self.paused = true;
+        for stream in self.streams.iter().filter(|stream| stream.playing) {
+            unsafe { raylib::ffi::PauseMusicStream(stream.music) };
+        }
+        for voice in self.sounds.values().flatten() {
+            unsafe { raylib::ffi::PauseSound(*voice) };
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if !self.paused {
+            return;
+        }
+        self.paused = false;
+        for stream in self.streams.iter().filter(|stream| stream.playing) {
+            unsafe { raylib::ffi::ResumeMusicStream(stream.music) };
+        }
+        for voice in self.sounds.values().flatten() {
+            unsafe { raylib::ffi::ResumeSound(*voice) };
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    // Silences everything; the next `update` starts playback from its time.
+    pub fn stop(&mut self) {
+        self.stop_sounds();
+        for stream in &mut self.streams {
+            stream.stop();
+        }
+        self.last_time = None;
+    }
+
+    fn stop_sounds(&mut self) {
+        for voice in self.sounds.values().flatten() {
+            unsafe { raylib::ffi::StopSound(*voice) };
+        }
+    }
+}
+
+impl Drop for PreviewAudio {
+    fn drop(&mut self) {
+        unsafe {
+            for voices in self.sounds.values() {
+                for alias in &voices[1..] {
+                    raylib::ffi::UnloadSoundAlias(*alias);
+                }
+                raylib::ffi::UnloadSound(voices[0]);
+            }
+            for stream in &self.streams {
+                raylib::ffi::UnloadMusicStream(stream.music);
+            }
+            // Another owner, such as `AudioEngine`, may still be using it.
+            if self.owns_device {
+                raylib::ffi::CloseAudioDevice();
+            }
+        }
+    }
+}
+
+impl PreviewStream {
+    // A stream with unity gain over its whole file; callers fill in placement.
+    fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            bail!("audio file not found: {}", path.display());
+        }
+        let c_path = CString::new(path.to_string_lossy().as_bytes())
+            .context("audio path contains null byte")?;
+        let mut music = unsafe { raylib::ffi::LoadMusicStream(c_path.as_ptr()) };
+        if music.stream.buffer.is_null() {
+            bail!("failed to load audio stream: {}", path.display());
+        }
+        music.looping = false;
+        let length = unsafe { raylib::ffi::GetMusicTimeLength(music) };
+        Ok(Self {
+            music,
+            span: StreamSpan::whole(length),
+            volume: 1.0,
+            envelope: Track::from_constant(1.0),
+            automation: None,
+            playing: false,
+        })
+    }
+
+    fn sync(&mut self, t: f32, force_seek: bool) {
+        let Some(pos) = self.span.source_position(t) else {
+            self.stop();
+            return;
+        };
+
+        self.music.looping = self.span.looped;
+        if !self.playing {
+            unsafe {
+                raylib::ffi::PlayMusicStream(self.music);
+                raylib::ffi::SeekMusicStream(self.music, pos);
+            }
+            self.playing = true;
+        } else {
+            let played = unsafe { raylib::ffi::GetMusicTimePlayed(self.music) };
+            if force_seek || self.span.drift(played, pos) > MAX_DRIFT {
+                unsafe { raylib::ffi::SeekMusicStream(self.music, pos) };
+            }
+        }
+
+        let local = t - self.span.start;
+        let automated = self
+            .automation
+            .as_ref()
+            .map_or(1.0, |curve| curve.sample(local));
+        let gain = self.volume * self.envelope.sample(local) * automated;
+        unsafe {
+            raylib::ffi::SetMusicVolume(self.music, gain);
+            raylib::ffi::UpdateMusicStream(self.music);
+        }
+    }
+
+    fn stop(&mut self) {
+        if self.playing {
+            unsafe { raylib::ffi::StopMusicStream(self.music) };
+            self.playing = false;
+        }
+    }
+}
+
+// Whether preview time moving from `last` to `t` is a seek rather than playback.
+fn is_jump(last: Option<f32>, t: f32) -> bool {
+    last.is_none_or(|last| t < last || t - last > SEEK_THRESHOLD)
+}
+
+// Where a stream's source of `length` seconds plays on the timeline: from
+// `start` until `end`, beginning `offset` seconds into the source.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct StreamSpan {
+    length: f32,
+    start: f32,
+    end: f32,
+    offset: f32,
+    looped: bool,
+}
+
+impl StreamSpan {
+    fn whole(length: f32) -> Self {
+        Self {
+            length,
+            start: 0.0,
+            end: f32::INFINITY,
+            offset: 0.0,
+            looped: false,
+        }
+    }
+
+    // Where in the source timeline time `t` falls, if the stream is audible then.
+    fn source_position(&self, t: f32) -> Option<f32> {
+        if t < self.start || t >= self.end {
+            return None;
+        }
+        let pos = self.offset + (t - self.start);
+        if self.looped && self.length > 0.0 {
+            Some(pos % self.length)
+        } else if pos < self.length {
+            Some(pos)
+        } else {
+            None
+        }
+    }
+
+    // How far the stream's own position `played` is from `pos`, the short way
+    // round when the stream loops.
+    fn drift(&self, played: f32, pos: f32) -> f32 {
+        let drift = (played - pos).abs();
+        if self.looped {
+            drift.min(self.length - drift)
+        } else {
+            drift
+        }
+    }
+}
+
+// The loaded sound plus aliases sharing its samples, so rapid repeats overlap
+// instead of restarting each other.
+fn load_voices(path: &Path) -> Result<Vec<raylib::ffi::Sound>> {
+    if !path.exists() {
+        bail!("sfx not found: {}", path.display());
+    }
+    let c_path =
+        CString::new(path.to_string_lossy().as_bytes()).context("sfx path contains null byte")?;
+    let sound = unsafe { raylib::ffi::LoadSound(c_path.as_ptr()) };
+    if sound.stream.buffer.is_null() {
+        bail!("failed to load sfx: {}", path.display());
+    }
+    let mut voices = vec![sound];
+    for _ in 1..VOICES {
+        voices.push(unsafe { raylib::ffi::LoadSoundAlias(sound) });
+    }
+    Ok(voices)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(start: f32, end: f32, offset: f32, looped: bool) -> StreamSpan {
+        StreamSpan {
+            start,
+            end,
+            offset,
+            looped,
+            ..StreamSpan::whole(4.0)
+        }
+    }
+
+    #[test]
+    fn maps_timeline_time_into_the_source() {
+        let once = span(2.0, 10.0, 1.0, false);
+        assert_eq!(once.source_position(1.9), None);
+        assert_eq!(once.source_position(2.0), Some(1.0));
+        assert_eq!(once.source_position(4.5), Some(3.5));
+        // Past the end of the file, before the span ends.
+        assert_eq!(once.source_position(5.0), None);
+        assert_eq!(once.source_position(12.0), None);
+
+        let looped = span(2.0, 10.0, 1.0, true);
+        assert_eq!(looped.source_position(4.5), Some(3.5));
+        assert_eq!(looped.source_position(5.0), Some(0.0));
+        assert_eq!(looped.source_position(9.5), Some(0.5));
+        assert_eq!(looped.source_position(10.0), None);
+
+        // Offsets past the first loop wrap too.
+        assert_eq!(span(0.0, 10.0, 6.0, true).source_position(0.0), Some(2.0));
+        assert_eq!(span(0.0, 10.0, 6.0, false).source_position(0.0), None);
+    }
+
+    #[test]
+    fn drift_wraps_around_loops() {
+        let once = span(0.0, 10.0, 0.0, false);
+        assert!((once.drift(3.9, 0.1) - 3.8).abs() < 1e-5);
+        assert!((once.drift(1.0, 1.05) - 0.05).abs() < 1e-5);
+        // Just before and just after the loop point are close.
+        let looped = span(0.0, 10.0, 0.0, true);
+        assert!((looped.drift(3.95, 0.02) - 0.07).abs() < 1e-5);
+        assert!(looped.drift(3.95, 0.02) < MAX_DRIFT);
+        assert!((looped.drift(1.0, 3.0) - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn large_or_backward_steps_are_seeks() {
+        assert!(is_jump(None, 0.0));
+        assert!(!is_jump(Some(1.0), 1.0 + 1.0 / 30.0));
+        assert!(!is_jump(Some(1.0), 1.0 + SEEK_THRESHOLD));
+        assert!(is_jump(Some(1.0), 1.5));
+        assert!(is_jump(Some(1.0), 0.99));
+    }
+}
@@ -1,17 +1,201 @@
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, PartialEq)]
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::audio::decode::probe_duration;
+use crate::scene::Track;
+
+// Music placed on the timeline from `start` to `end` (seconds), playing the
+// source from `source_offset` seconds in. Looped tracks wrap back to the start
+// of the file; unlooped ones go silent when the file runs out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MusicTrack {
     pub path: PathBuf,
     pub start: f32,
     pub end: f32,
     pub looped: bool,
     pub volume: f32,
+    #[serde(default)]
+    pub source_offset: f32,
+    // Linear ramps (seconds) up from silence at `start` and down to silence at `end`.
+    #[serde(default)]
+    pub fade_in: f32,
+    #[serde(default)]
+    pub fade_out: f32,
+    // Overlap with the previous track (by start time). That track keeps
+    // playing until `start + crossfade` and the two swap with an equal-power
+    // curve; this replaces both tracks' plain fades over the overlap.
+    #[serde(default)]
+    pub crossfade: f32,
+    // Gain multiplier over track-local time (0 = `start`), on top of `volume`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automation: Option<Track<f32>>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SfxEvent {
     pub path: PathBuf,
     pub time: f32,
     pub volume: f32,
+    // Gain multiplier over event-local time (0 = `time`), on top of `volume`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automation: Option<Track<f32>>,
+    // Tape-style playback speed: 2.0 plays twice as fast and an octave higher.
+    #[serde(default = "default_rate")]
+    pub rate: f32,
+    #[serde(default)]
+    pub reverse: bool,
+    // Per-event random pitch/volume offsets, repeatable from the seed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variation: Option<SfxVariation>,
+}
+
+// Each event draws its offsets from `seed` and its own time, so events sharing
+// one variation still differ from each other but render the same every time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SfxVariation {
+    pub seed: u64,
+    // Maximum offset either way, in semitones and dB.
+    #[serde(default)]
+    pub pitch: f32,
+    #[serde(default)]
+    pub volume_db: f32,
+}
+
+fn default_rate() -> f32 {
+    1.0
+}
+
+impl SfxEvent {
+    pub fn new(path: impl Into<PathBuf>, time: f32, volume: f32) -> Self {
+        Self {
+            path: path.into(),
+            time,
+            volume,
+            automation: None,
+            rate: 1.0,
+            reverse: false,
+            variation: None,
+        }
+    }
+
+    // Source frames consumed per output frame, including any variation.
+    pub fn playback_rate(&self) -> f32 {
+        let semitones = self.variation.map_or(0.0, |v| v.pitch * self.random(0));
+        self.rate * 2f32.powf(semitones / 12.0)
+    }
+
+    // `volume` with the variation's random offset applied.
+    pub fn gain(&self) -> f32 {
+        let db = self.variation.map_or(0.0, |v| v.volume_db * self.random(1));
+        self.volume * 10f32.powf(db / 20.0)
+    }
+
+    // Deterministic value in -1..=1 for this event's `stream`th random draw.
+    fn random(&self, stream: u64) -> f32 {
+        let Some(variation) = self.variation else {
+            return 0.0;
+        };
+        let mut x = variation.seed
+            ^ (self.time.to_bits() as u64).rotate_left(32)
+            ^ stream.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        // splitmix64 finalizer
+        x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        x ^= x >> 31;
+        (x >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+}
+
+// A narration or dialogue line: the source from `trim_start` to `trim_end`
+// (seconds into the file) placed at `start_time` on the timeline. Without
+// `trim_end` it plays to the end of the file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DialogueClip {
+    pub path: PathBuf,
+    pub start_time: f32,
+    #[serde(default)]
+    pub trim_start: Option<f32>,
+    #[serde(default)]
+    pub trim_end: Option<f32>,
+    #[serde(default = "unity_gain")]
+    pub gain: f32,
+}
+
+fn unity_gain() -> f32 {
+    1.0
+}
+
+impl DialogueClip {
+    pub fn new(
+        path: impl Into<PathBuf>,
+        start_time: f32,
+        trim_start: Option<f32>,
+        trim_end: Option<f32>,
+        gain: f32,
+    ) -> Result<Self> {
+        let clip = Self {
+            path: path.into(),
+            start_time,
+            trim_start,
+            trim_end,
+            gain,
+        };
+        clip.validate()?;
+        Ok(clip)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if !self.path.exists() {
+            bail!("dialogue clip not found: {}", self.path.display());
+        }
+        if self.start_time < 0.0 {
+            bail!("dialogue start_time must be >= 0");
+        }
+        if self.trim_start.is_some_and(|ts| ts < 0.0) {
+            bail!("trim_start must be >= 0");
+        }
+        if let Some(te) = self.trim_end
+            && te <= self.source_start()
+        {
+            bail!("trim_end must be > trim_start");
+        }
+        if self.gain < 0.0 {
+            bail!("dialogue gain must be >= 0");
+        }
+        Ok(())
+    }
+
+    // Also checks that the clip ends within the timeline. Untrimmed clips play
+    // to the end of the file, so their length is read from it.
+    pub fn validate_against(&self, duration: f32) -> Result<()> {
+        self.validate()?;
+        if self.start_time >= duration {
+            bail!("dialogue must start before the timeline ends");
+        }
+        let length = match self.duration() {
+            Some(length) => length,
+            None => probe_duration(&self.path)? - self.source_start(),
+        };
+        if length <= 0.0 {
+            bail!("trim_start must be before the end of the file");
+        }
+        if self.start_time + length > duration {
+            bail!(
+                "dialogue ends at {:.3}s, after timeline duration {duration}",
+                self.start_time + length
+            );
+        }
+        Ok(())
+    }
+
+    pub fn source_start(&self) -> f32 {
+        self.trim_start.unwrap_or(0.0)
+    }
+
+    // Timeline length, when the trim makes it known without decoding.
+    pub fn duration(&self) -> Option<f32> {
+        self.trim_end.map(|te| te - self.source_start())
+    }
 }
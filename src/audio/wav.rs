@@ -0,0 +1,50 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use crate::audio::mixer::AudioBuffer;
+
+// Writes 16-bit PCM WAV. Samples outside -1..1 are clipped.
+pub fn write_wav(buffer: &AudioBuffer, path: &Path) -> Result<()> {
+    let file =
+        File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+    let mut out = BufWriter::new(file);
+    out.write_all(&wav_bytes(buffer)?)?;
+    out.flush()
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(())
+}
+
+pub fn wav_bytes(buffer: &AudioBuffer) -> Result<Vec<u8>> {
+    let channels = AudioBuffer::CHANNELS;
+    let data_len = buffer.samples.len() * 2;
+    if data_len > (u32::MAX - 36) as usize {
+        bail!("audio too long for a WAV file");
+    }
+    let byte_rate = buffer.sample_rate * channels as u32 * 2;
+
+    let mut bytes = Vec::with_capacity(44 + data_len);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len as u32).to_le_bytes());
+    bytes.extend_from_slice(b"WAVE");
+    bytes.extend_from_slice(b"fmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&channels.to_le_bytes());
+    bytes.extend_from_slice(&buffer.sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&byte_rate.to_le_bytes());
+    bytes.extend_from_slice(&(channels * 2).to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&(data_len as u32).to_le_bytes());
+    for sample in &buffer.samples {
+        bytes.extend_from_slice(&to_i16(*sample).to_le_bytes());
+    }
+    Ok(bytes)
+}
+
+fn to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16
+}
@@ -0,0 +1,40 @@
+use crate::scene::{Anchor, Transform, Vec2};
+
+// Offset from the top-left corner of an unscaled `width` x `height` box to the
+// transform's anchor, after scaling. Screen orientation (+y down).
+pub(crate) fn anchor_offset(transform: &Transform, default: Anchor, width: f32, height: f32) -> Vec2 {
+    let offset = transform.anchor_or(default).offset(width, height);
+    Vec2 {
+        x: offset.x * transform.scale.x,
+        y: offset.y * transform.scale.y,
+    }
+}
+
+// Rotates a screen-space vector clockwise on screen by `degrees`, matching
+// raylib's `*_pro` draw calls.
+pub(crate) fn rotate_screen(v: Vec2, degrees: f32) -> Vec2 {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    Vec2 {
+        x: v.x * cos - v.y * sin,
+        y: v.x * sin + v.y * cos,
+    }
+}
+
+// Text scales uniformly with `scale.y` and is laid out at the scaled font size,
+// so `width` and `height` here are already scaled.
+pub(crate) fn text_anchor_offset(
+    transform: &Transform,
+    default: Anchor,
+    width: f32,
+    height: f32,
+) -> Vec2 {
+    let scale = transform.scale.y.max(0.0);
+    if scale <= 0.0 {
+        return Vec2::ZERO;
+    }
+    let offset = transform.anchor_or(default).offset(width / scale, height / scale);
+    Vec2 {
+        x: offset.x * scale,
+        y: offset.y * scale,
+    }
+}
@@ -0,0 +1,167 @@
+use std::time::Instant;
+
+use anyhow::{bail, Result};
+
+use crate::timeline::{SampledScene, Timeline};
+
+// Shared frame loop for offline backends: samples the timeline at fixed fps,
+// hands each scene to `render`, and forwards the RGBA frame to `on_frame`.
+pub(crate) fn render_frames(
+    timeline: &Timeline,
+    start_time: f32,
+    end_time: f32,
+    progress: Option<RenderProgress>,
+    mut render: impl FnMut(&SampledScene) -> Result<Vec<u8>>,
+    mut on_frame: impl FnMut(f32, &[u8]) -> Result<()>,
+) -> Result<()> {
+    if start_time < 0.0 || end_time <= start_time || end_time > timeline.duration {
+        bail!("start/end time must satisfy 0 <= start < end <= duration");
+    }
+
+    let frames = ((end_time - start_time) * timeline.fps as f32).floor() as u32;
+    let mut log = ProgressLog::new(progress.unwrap_or_default(), frames, timeline.fps);
+
+    for i in 0..frames {
+        let t = start_time + i as f32 / timeline.fps as f32;
+        let scene = timeline.sample(t)?;
+        let rgba = render(&scene)?;
+        on_frame(t, &rgba)?;
+        log.frame_done(i + 1);
+    }
+
+    Ok(())
+}
+
+// Progress lines for the offline render loops, so the plain and pipelined
+// renders report the same way. The ETA uses the speed over the last 100
+// frames; until then the line shows the elapsed time instead.
+pub(crate) struct ProgressLog {
+    progress: RenderProgress,
+    frames: u32,
+    fps: u32,
+    started: Instant,
+    last_logged: u32,
+    window_frame: u32,
+    window_start: Instant,
+    per_frame_secs: Option<f32>,
+}
+
+impl ProgressLog {
+    pub(crate) fn new(progress: RenderProgress, frames: u32, fps: u32) -> Self {
+        let now = Instant::now();
+        Self {
+            progress,
+            frames,
+            fps,
+            started: now,
+            last_logged: 0,
+            window_frame: 0,
+            window_start: now,
+            per_frame_secs: None,
+        }
+    }
+
+    // Call after each frame with the number of frames finished so far.
+    pub(crate) fn frame_done(&mut self, done: u32) {
+        if !self.progress.enabled {
+            return;
+        }
+        if done - self.window_frame >= 100 {
+            let window = done - self.window_frame;
+            self.per_frame_secs = Some(self.window_start.elapsed().as_secs_f32() / window as f32);
+            self.window_frame = done;
+            self.window_start = Instant::now();
+        }
+        if done - self.last_logged >= self.progress.log_every_frames {
+            self.last_logged = done;
+            println!("{}", self.line(done, self.started.elapsed().as_secs_f32()));
+        }
+    }
+
+    fn line(&self, done: u32, elapsed_secs: f32) -> String {
+        let frames = self.frames;
+        let percent = done as f32 / frames.max(1) as f32 * 100.0;
+        let mut line = format!("frames: {done}/{frames} ({percent:.1}%)");
+
+        if self.progress.show_time {
+            let rendered_secs = done as f32 / self.fps as f32;
+            let total_secs = frames as f32 / self.fps as f32;
+            line.push_str(&format!(
+                " time {}/{}",
+                format_hms(rendered_secs),
+                format_hms(total_secs)
+            ));
+            if self.progress.show_eta {
+                if let Some(pf) = self.per_frame_secs {
+                    let eta = frames.saturating_sub(done) as f32 * pf;
+                    line.push_str(&format!(" eta {}", format_hms(eta)));
+                } else {
+                    line.push_str(&format!(" elapsed {}", format_hms(elapsed_secs)));
+                }
+            }
+        }
+        line
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RenderProgress {
+    pub enabled: bool,
+    pub log_every_frames: u32,
+    pub show_time: bool,
+    pub show_eta: bool,
+}
+
+impl Default for RenderProgress {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            log_every_frames: 100,
+            show_time: true,
+            show_eta: true,
+        }
+    }
+}
+
+pub(crate) fn format_hms(seconds: f32) -> String {
+    let total = seconds.max(0.0).round() as u64;
+    let h = total / 3600;
+    let m = (total % 3600) / 60;
+    let s = total % 60;
+    format!("{:02}:{:02}:{:02}", h, m, s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress_log(show_time: bool, show_eta: bool) -> ProgressLog {
+        let progress = RenderProgress {
+            enabled: true,
+            log_every_frames: 100,
+            show_time,
+            show_eta,
+        };
+        ProgressLog::new(progress, 3000, 30)
+    }
+
+    #[test]
+    fn formats_progress_lines() {
+        let mut log = progress_log(true, true);
+        assert_eq!(
+            log.line(60, 4.0),
+            "frames: 60/3000 (2.0%) time 00:00:02/00:01:40 elapsed 00:00:04"
+        );
+        log.per_frame_secs = Some(0.5);
+        assert_eq!(
+            log.line(600, 300.0),
+            "frames: 600/3000 (20.0%) time 00:00:20/00:01:40 eta 00:20:00"
+        );
+
+        assert_eq!(
+            progress_log(true, false).line(600, 300.0),
+            "frames: 600/3000 (20.0%) time 00:00:20/00:01:40"
+        );
+        assert_eq!(progress_log(false, true).line(600, 300.0), "frames: 600/3000 (20.0%)");
+    }
+}
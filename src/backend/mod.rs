@@ -1,4 +1,12 @@
+pub mod anchor;
+pub mod frame_loop;
+pub mod pipeline;
+pub mod raylib_draw;
 pub mod raylib_preview;
 pub mod raylib_render;
+pub mod renderer;
 pub mod resources;
+pub mod software;
+pub mod stills;
+pub mod text_layout;
 pub mod text_render;
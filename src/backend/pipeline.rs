@@ -0,0 +1,327 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::backend::frame_loop::{ProgressLog, RenderProgress};
+use crate::encoder::FrameSink;
+use crate::scene::{FontSource, Object};
+use crate::timeline::{SampledScene, Timeline};
+
+// Tuning for `Renderer::render_timeline_pipelined`.
+#[derive(Debug, Clone, Copy)]
+pub struct PipelineOptions {
+    // Threads sampling the timeline ahead of the renderer.
+    pub sample_workers: usize,
+    // Frames buffered between stages. Bounds memory at roughly
+    // `2 * queue_depth` frames plus scenes.
+    pub queue_depth: usize,
+    pub progress: RenderProgress,
+}
+
+impl Default for PipelineOptions {
+    // Leaves one core each for the render and encode threads.
+    fn default() -> Self {
+        let cores = thread::available_parallelism().map_or(4, |n| n.get());
+        Self {
+            sample_workers: cores.saturating_sub(2).clamp(1, 8),
+            queue_depth: 8,
+            progress: RenderProgress::default(),
+        }
+    }
+}
+
+// Where a pipelined render spent its time. The render thread's waits show
+// which stage limited throughput.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RenderStats {
+    pub frames: u32,
+    pub elapsed: Duration,
+    // Render thread: drawing and capture.
+    pub render: Duration,
+    // Render thread: blocked on the samplers (sampling-bound).
+    pub waiting_for_scenes: Duration,
+    // Render thread: blocked on a full frame queue (encoder-bound).
+    pub waiting_for_encoder: Duration,
+    // Encode thread: inside `FrameSink::write_frame`.
+    pub encode: Duration,
+}
+
+impl RenderStats {
+    pub fn fps(&self) -> f32 {
+        self.frames as f32 / self.elapsed.as_secs_f32().max(1e-6)
+    }
+
+    pub fn bottleneck(&self) -> &'static str {
+        let waiting = self.waiting_for_scenes.max(self.waiting_for_encoder);
+        if waiting.as_secs_f32() < 0.1 * self.elapsed.as_secs_f32() {
+            "render"
+        } else if self.waiting_for_encoder >= self.waiting_for_scenes {
+            "encode"
+        } else {
+            "sampling"
+        }
+    }
+}
+
+impl fmt::Display for RenderStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} frames in {:.1}s ({:.1} fps, {}-bound): render {:.1}s, encode {:.1}s, \
+             waited {:.1}s for scenes and {:.1}s for the encoder",
+            self.frames,
+            self.elapsed.as_secs_f32(),
+            self.fps(),
+            self.bottleneck(),
+            self.render.as_secs_f32(),
+            self.encode.as_secs_f32(),
+            self.waiting_for_scenes.as_secs_f32(),
+            self.waiting_for_encoder.as_secs_f32()
+        )
+    }
+}
+
+// Three stages joined by bounded queues:
+//
+// - sampler threads evaluate the timeline for interleaved frames and read
+//   each asset file once ahead of use, so loads on the render thread hit the
+//   OS cache;
+// - the calling thread draws and captures (raylib's GL context lives there);
+// - an encode thread feeds `sink`, so a blocking ffmpeg pipe no longer
+//   stalls drawing.
+//
+// Frame times match `render_frames`, and frames reach `sink` in order.
+pub(crate) fn render_pipelined(
+    timeline: &Timeline,
+    start_time: f32,
+    end_time: f32,
+    options: &PipelineOptions,
+    mut render: impl FnMut(&SampledScene) -> Result<Vec<u8>>,
+    sink: &mut (impl FrameSink + Send),
+) -> Result<RenderStats> {
+    if start_time < 0.0 || end_time <= start_time || end_time > timeline.duration {
+        bail!("start/end time must satisfy 0 <= start < end <= duration");
+    }
+
+    let frames = ((end_time - start_time) * timeline.fps as f32).floor() as u32;
+    let workers = options.sample_workers.max(1);
+    let depth = options.queue_depth.max(1);
+    let warmed = Mutex::new(HashSet::new());
+    let started = Instant::now();
+    let mut log = ProgressLog::new(options.progress, frames, timeline.fps);
+    let mut stats = RenderStats::default();
+
+    let result = thread::scope(|scope| {
+        // Worker `k` samples frames k, k + workers, ...; the render thread
+        // reads the queues round-robin, which restores frame order.
+        let mut scenes: Vec<Receiver<Result<SampledScene>>> = Vec::with_capacity(workers);
+        for worker in 0..workers {
+            let (tx, rx) = sync_channel(depth.div_ceil(workers));
+            scenes.push(rx);
+            let warmed = &warmed;
+            scope.spawn(move || {
+                for i in (worker..frames as usize).step_by(workers) {
+                    let t = start_time + i as f32 / timeline.fps as f32;
+                    let scene = timeline.sample(t);
+                    if let Ok(scene) = &scene {
+                        warm_asset_files(scene, warmed);
+                    }
+                    // The render thread stopped early.
+                    if tx.send(scene).is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+
+        let (frame_tx, frame_rx) = sync_channel::<Vec<u8>>(depth);
+        let encoder = scope.spawn(move || -> Result<Duration> {
+            let mut encode = Duration::ZERO;
+            for frame in frame_rx {
+                let begin = Instant::now();
+                sink.write_frame(&frame)?;
+                encode += begin.elapsed();
+            }
+            Ok(encode)
+        });
+
+        let mut rendered = Ok(());
+        for i in 0..frames {
+            let begin = Instant::now();
+            let scene = scenes[i as usize % workers]
+                .recv()
+                .map_err(|_| anyhow!("timeline sampler stopped"))
+                .and_then(|scene| scene);
+            stats.waiting_for_scenes += begin.elapsed();
+            let scene = match scene {
+                Ok(scene) => scene,
+                Err(e) => {
+                    rendered = Err(e);
+                    break;
+                }
+            };
+
+            let begin = Instant::now();
+            let rgba = match render(&scene) {
+                Ok(rgba) => rgba,
+                Err(e) => {
+                    rendered = Err(e);
+                    break;
+                }
+            };
+            stats.render += begin.elapsed();
+
+            let begin = Instant::now();
+            // A closed queue means the encoder failed; its error is reported below.
+            if frame_tx.send(rgba).is_err() {
+                break;
+            }
+            stats.waiting_for_encoder += begin.elapsed();
+            stats.frames = i + 1;
+
+            log.frame_done(stats.frames);
+        }
+        drop(frame_tx);
+        drop(scenes);
+
+        let encoded = encoder
+            .join()
+            .map_err(|_| anyhow!("encode thread panicked"))?;
+        rendered?;
+        stats.encode = encoded?;
+        Ok(())
+    });
+
+    result.map(|()| {
+        stats.elapsed = started.elapsed();
+        stats
+    })
+}
+
+// Reads every image and font file the scene uses the first time any worker
+// sees it and throws the bytes away. This only warms the OS file cache, so the
+// render thread's loads skip the disk; decoding still happens there, because
+// raylib textures must be created on the thread that owns the GL context.
+// Errors are left for the render thread's load to report.
+fn warm_asset_files(scene: &SampledScene, seen: &Mutex<HashSet<PathBuf>>) {
+    let mut paths = Vec::new();
+    for clip in scene.layers.iter().flat_map(|layer| &layer.clips) {
+        match &clip.object {
+            Object::Image(image) => paths.push(&image.path),
+            Object::Text(text) => {
+                let family = &text.font;
+                let sources = [
+                    Some(&family.regular),
+                    family.bold.as_ref(),
+                    family.italic.as_ref(),
+                    family.bold_italic.as_ref(),
+                ];
+                for source in sources.into_iter().flatten() {
+                    if let FontSource::Path(path) = source {
+                        paths.push(path);
+                    }
+                }
+            }
+            Object::Shape(_) | Object::Group(_) => {}
+        }
+    }
+    for path in paths {
+        let first = seen
+            .lock()
+            .map(|mut seen| seen.insert(path.clone()))
+            .unwrap_or(false);
+        if first {
+            let _ = std::fs::read(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::renderer::Renderer;
+    use crate::backend::software::SoftwareRender;
+    use crate::scene::{AnimatedTransform, Color, Easing, Keyframe, Shape, Track, Vec2};
+    use crate::timeline::{Clip, Layer};
+
+    // A square sliding across the frame, so every frame differs.
+    fn timeline() -> Timeline {
+        let mut timeline = Timeline::new(1.0, 24).unwrap();
+        let mut layer = Layer::new("slide");
+        let square = Object::Shape(Shape::Rect {
+            width: 10.0,
+            height: 10.0,
+            color: Color::WHITE,
+        });
+        let transform = AnimatedTransform {
+            position: Track::new(vec![
+                Keyframe::new(0.0, Vec2 { x: -30.0, y: 0.0 }, Easing::Linear),
+                Keyframe::new(1.0, Vec2 { x: 30.0, y: 0.0 }, Easing::Linear),
+            ])
+            .unwrap(),
+            ..AnimatedTransform::default()
+        };
+        layer.add_clip(Clip::new(0.0, 1.0, square, transform, 1.0).unwrap());
+        timeline.add_layer(layer);
+        timeline
+    }
+
+    #[test]
+    fn pipelined_frames_match_serial_render() {
+        let timeline = timeline();
+        let mut renderer = SoftwareRender::new(80, 40, Color::BLACK).unwrap();
+        let mut serial = Vec::new();
+        renderer
+            .render_timeline_rgba(&timeline, 0.25, 1.0, |_t, rgba| {
+                serial.push(rgba.to_vec());
+                Ok(())
+            })
+            .unwrap();
+
+        let options = PipelineOptions {
+            sample_workers: 3,
+            queue_depth: 2,
+            ..PipelineOptions::default()
+        };
+        let mut piped: Vec<Vec<u8>> = Vec::new();
+        let stats = renderer
+            .render_timeline_pipelined(&timeline, 0.25, 1.0, &options, &mut piped)
+            .unwrap();
+        assert_eq!(stats.frames, 18);
+        assert_eq!(piped.len(), serial.len());
+        assert!(piped == serial);
+    }
+
+    struct FailingSink(u32);
+
+    impl FrameSink for FailingSink {
+        fn write_frame(&mut self, _frame: &[u8]) -> Result<()> {
+            self.0 += 1;
+            if self.0 == 3 {
+                bail!("disk full");
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn encoder_errors_stop_the_render() {
+        let timeline = timeline();
+        let mut renderer = SoftwareRender::new(80, 40, Color::BLACK).unwrap();
+        let options = PipelineOptions {
+            sample_workers: 2,
+            queue_depth: 1,
+            ..PipelineOptions::default()
+        };
+        let err = renderer
+            .render_timeline_pipelined(&timeline, 0.0, 1.0, &options, &mut FailingSink(0))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "disk full");
+    }
+}
@@ -0,0 +1,114 @@
+use std::path::Path;
+
+use anyhow::Result;
+use raylib::prelude::*;
+
+use crate::backend::anchor::{anchor_offset, rotate_screen};
+use crate::backend::resources::ResourceCache;
+use crate::backend::text_render::draw_text_block;
+use crate::scene::{Anchor, Color, Object, Shape, Transform, Vec2};
+
+// Draw helpers shared by the raylib preview window and the offline renderer.
+pub(crate) fn draw_object(
+    d: &mut impl RaylibDraw,
+    cache: &ResourceCache,
+    width: u32,
+    height: u32,
+    object: &Object,
+    transform: &Transform,
+) -> Result<()> {
+    match object {
+        Object::Shape(shape) => draw_shape(d, width, height, shape, transform),
+        Object::Image(image) => draw_image(d, cache, width, height, &image.path, transform),
+        Object::Text(text) => draw_text_block(d, cache, width, height, text, transform),
+        // Flattened by `Timeline::sample`.
+        Object::Group(_) => Ok(()),
+    }
+}
+
+pub(crate) fn draw_shape(
+    d: &mut impl RaylibDraw,
+    width: u32,
+    height: u32,
+    shape: &Shape,
+    transform: &Transform,
+) -> Result<()> {
+    let pivot = graph_to_screen(transform.pos, width, height);
+    let color = to_raylib_color(
+        match shape {
+            Shape::Circle { color, .. } => *color,
+            Shape::Rect { color, .. } => *color,
+        },
+        transform.opacity,
+    );
+
+    match shape {
+        Shape::Circle { radius, .. } => {
+            let scale = transform.scale.x.max(0.0);
+            let scaled = radius * scale;
+            // Circles scale uniformly, so place the center relative to the
+            // anchor and rotate it around the pivot.
+            let uniform = Transform {
+                scale: Vec2 { x: scale, y: scale },
+                ..*transform
+            };
+            let anchor = anchor_offset(&uniform, Anchor::CENTER, radius * 2.0, radius * 2.0);
+            let offset = rotate_screen(
+                Vec2 {
+                    x: scaled - anchor.x,
+                    y: scaled - anchor.y,
+                },
+                transform.rotation,
+            );
+            let center = Vector2::new(pivot.x + offset.x, pivot.y + offset.y);
+            d.draw_circle_v(center, scaled, color);
+        }
+        Shape::Rect { width: w, height: h, .. } => {
+            let anchor = anchor_offset(transform, Anchor::CENTER, *w, *h);
+            let w = w * transform.scale.x;
+            let h = h * transform.scale.y;
+            let rec = Rectangle::new(pivot.x, pivot.y, w, h);
+            let origin = Vector2::new(anchor.x, anchor.y);
+            d.draw_rectangle_pro(rec, origin, transform.rotation, color);
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn draw_image(
+    d: &mut impl RaylibDraw,
+    cache: &ResourceCache,
+    width: u32,
+    height: u32,
+    path: &Path,
+    transform: &Transform,
+) -> Result<()> {
+    let texture = cache.get_texture(path)?;
+    let tex_w = texture.width as f32;
+    let tex_h = texture.height as f32;
+
+    let w = tex_w * transform.scale.x;
+    let h = tex_h * transform.scale.y;
+    let pivot = graph_to_screen(transform.pos, width, height);
+    let anchor = anchor_offset(transform, Anchor::CENTER, tex_w, tex_h);
+
+    let source = Rectangle::new(0.0, 0.0, tex_w, tex_h);
+    let dest = Rectangle::new(pivot.x, pivot.y, w, h);
+    let origin = Vector2::new(anchor.x, anchor.y);
+
+    let tint = to_raylib_color(Color::WHITE, transform.opacity);
+    d.draw_texture_pro(texture, source, dest, origin, transform.rotation, tint);
+    Ok(())
+}
+
+pub(crate) fn graph_to_screen(pos: Vec2, width: u32, height: u32) -> Vector2 {
+    Vector2::new(width as f32 / 2.0 + pos.x, height as f32 / 2.0 - pos.y)
+}
+
+pub(crate) fn to_raylib_color(color: Color, opacity: f32) -> raylib::prelude::Color {
+    let alpha = (color.a as f32 * opacity.clamp(0.0, 1.0))
+        .round()
+        .clamp(0.0, 255.0) as u8;
+    raylib::prelude::Color::new(color.r, color.g, color.b, alpha)
+}
@@ -1,14 +1,15 @@
-use std::path::Path;
-
 use anyhow::{bail, Result};
-use raylib::consts::TraceLogLevel;
+use raylib::consts::{KeyboardKey, TraceLogLevel};
 use raylib::prelude::*;
 
+use crate::audio::PreviewAudio;
+use crate::backend::raylib_draw::{draw_object, to_raylib_color};
 use crate::backend::resources::ResourceCache;
-use crate::backend::text_render::draw_text_block;
-use crate::scene::{Color, Object, Shape, Transform, Vec2};
+use crate::scene::Color;
 use crate::timeline::{SampledScene, Timeline};
 
+const SEEK_STEP: f32 = 5.0;
+
 pub struct RaylibPreview {
     width: u32,
     height: u32,
@@ -48,6 +49,34 @@ impl RaylibPreview {
         timeline: &Timeline,
         start_time: f32,
         end_time: f32,
+        per_frame: F,
+    ) -> Result<()>
+    where
+        F: FnMut(f32) -> Result<()>,
+    {
+        self.run_loop(timeline, start_time, end_time, None, per_frame)
+    }
+
+    // Plays `audio` in sync with the preview, including across pauses, seeks
+    // and restarts.
+    pub fn run_with_audio(
+        &self,
+        timeline: &Timeline,
+        start_time: f32,
+        end_time: f32,
+        audio: &mut PreviewAudio,
+    ) -> Result<()> {
+        self.run_loop(timeline, start_time, end_time, Some(audio), |_| Ok(()))
+    }
+
+    // Space pauses, Left/Right seek by SEEK_STEP seconds, R or Home restarts
+    // from `start_time`.
+    fn run_loop<F>(
+        &self,
+        timeline: &Timeline,
+        start_time: f32,
+        end_time: f32,
+        mut audio: Option<&mut PreviewAudio>,
         mut per_frame: F,
     ) -> Result<()>
     where
@@ -67,17 +96,55 @@ impl RaylibPreview {
         let mut cache = ResourceCache::new();
         let dt = 1.0 / timeline.fps as f32;
         let mut t = start_time;
+        let mut paused = false;
 
         while t < end_time {
             if rl.window_should_close() {
                 break;
             }
+            if rl.is_key_pressed(KeyboardKey::KEY_SPACE) {
+                paused = !paused;
+                if let Some(audio) = audio.as_deref_mut() {
+                    if paused {
+                        audio.pause();
+                    } else {
+                        audio.resume();
+                    }
+                }
+            }
+            let before = t;
+            if rl.is_key_pressed(KeyboardKey::KEY_R) || rl.is_key_pressed(KeyboardKey::KEY_HOME) {
+                t = start_time;
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_LEFT) {
+                t = (t - SEEK_STEP).max(start_time);
+            }
+            if rl.is_key_pressed(KeyboardKey::KEY_RIGHT) {
+                t = (t + SEEK_STEP).min(end_time - dt).max(start_time);
+            }
+            // Running audio notices the jump on its next update; paused audio
+            // has to be told so it resumes from the new time.
+            if paused
+                && t != before
+                && let Some(audio) = audio.as_deref_mut()
+            {
+                audio.seek(t);
+            }
+
             let scene = timeline.sample(t)?;
             self.draw_scene(&mut rl, &thread, &mut cache, &scene)?;
-            per_frame(t)?;
-            t += dt;
+            if !paused {
+                if let Some(audio) = audio.as_deref_mut() {
+                    audio.update(t);
+                }
+                per_frame(t)?;
+                t += dt;
+            }
         }
 
+        if let Some(audio) = audio {
+            audio.stop();
+        }
         Ok(())
     }
 
@@ -109,87 +176,3 @@ impl RaylibPreview {
         Ok(())
     }
 }
-
-fn draw_object(
-    d: &mut RaylibDrawHandle,
-    cache: &ResourceCache,
-    width: u32,
-    height: u32,
-    object: &Object,
-    transform: &Transform,
-) -> Result<()> {
-    match object {
-        Object::Shape(shape) => draw_shape(d, width, height, shape, transform),
-        Object::Image(image) => draw_image(d, cache, width, height, &image.path, transform),
-        Object::Text(text) => draw_text_block(d, cache, width, height, text, transform),
-    }
-}
-
-fn draw_shape(
-    d: &mut RaylibDrawHandle,
-    width: u32,
-    height: u32,
-    shape: &Shape,
-    transform: &Transform,
-) -> Result<()> {
-    let center = graph_to_screen(transform.pos, width, height);
-    let color = to_raylib_color(
-        match shape {
-            Shape::Circle { color, .. } => *color,
-            Shape::Rect { color, .. } => *color,
-        },
-        transform.opacity,
-    );
-
-    match shape {
-        Shape::Circle { radius, .. } => {
-            let scaled = radius * transform.scale.x.max(0.0);
-            d.draw_circle_v(center, scaled, color);
-        }
-        Shape::Rect { width: w, height: h, .. } => {
-            let w = w * transform.scale.x;
-            let h = h * transform.scale.y;
-            let rec = Rectangle::new(center.x, center.y, w, h);
-            let origin = Vector2::new(w / 2.0, h / 2.0);
-            d.draw_rectangle_pro(rec, origin, transform.rotation, color);
-        }
-    }
-
-    Ok(())
-}
-
-fn draw_image(
-    d: &mut RaylibDrawHandle,
-    cache: &ResourceCache,
-    width: u32,
-    height: u32,
-    path: &Path,
-    transform: &Transform,
-) -> Result<()> {
-    let texture = cache.get_texture(path)?;
-    let tex_w = texture.width as f32;
-    let tex_h = texture.height as f32;
-
-    let w = tex_w * transform.scale.x;
-    let h = tex_h * transform.scale.y;
-    let center = graph_to_screen(transform.pos, width, height);
-
-    let source = Rectangle::new(0.0, 0.0, tex_w, tex_h);
-    let dest = Rectangle::new(center.x, center.y, w, h);
-    let origin = Vector2::new(w / 2.0, h / 2.0);
-
-    let tint = to_raylib_color(Color::WHITE, transform.opacity);
-    d.draw_texture_pro(texture, source, dest, origin, transform.rotation, tint);
-    Ok(())
-}
-
-fn graph_to_screen(pos: Vec2, width: u32, height: u32) -> Vector2 {
-    Vector2::new(width as f32 / 2.0 + pos.x, height as f32 / 2.0 - pos.y)
-}
-
-fn to_raylib_color(color: Color, opacity: f32) -> raylib::prelude::Color {
-    let alpha = (color.a as f32 * opacity.clamp(0.0, 1.0))
-        .round()
-        .clamp(0.0, 255.0) as u8;
-    raylib::prelude::Color::new(color.r, color.g, color.b, alpha)
-}
@@ -0,0 +1,170 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::backend::frame_loop::{render_frames, RenderProgress};
+use crate::backend::pipeline::{render_pipelined, PipelineOptions, RenderStats};
+use crate::backend::stills::{save_png, save_sheet, ContactSheet};
+use crate::backend::text_layout::TextMetrics;
+use crate::encoder::{flip_rows, FrameSink, ImageSequenceWriter, SequenceFormat};
+use crate::scene::TextObject;
+use crate::timeline::{SampledScene, Timeline};
+
+// Offline backend that turns sampled scenes into RGBA frames. Frames are
+// `width * height * 4` bytes, bottom-up, ready for `FfmpegVideoEncoder::write_frame`.
+pub trait Renderer {
+    fn size(&self) -> (u32, u32);
+
+    // Load every asset the scene references. `render_scene_to_rgba` calls this
+    // itself; call it ahead of time to surface missing assets before a render.
+    fn preload_for_scene(&mut self, scene: &SampledScene) -> Result<()>;
+
+    // Drop cached textures and fonts; they are reloaded on next use.
+    fn clear_resources(&mut self);
+
+    fn render_scene_to_rgba(&mut self, scene: &SampledScene) -> Result<Vec<u8>>;
+
+    // Lays out `text` at its own font size (no transform) with this backend's
+    // fonts, loading them if needed, and reports the block size.
+    fn measure_text(&mut self, text: &TextObject) -> Result<TextMetrics>;
+
+    // Samples `timeline` at `t` and renders that one frame.
+    fn render_frame_rgba(&mut self, timeline: &Timeline, t: f32) -> Result<Vec<u8>> {
+        let scene = timeline.sample(t)?;
+        self.render_scene_to_rgba(&scene)
+    }
+
+    fn render_frame_png(&mut self, timeline: &Timeline, t: f32, path: &Path) -> Result<()> {
+        let rgba = self.render_frame_rgba(timeline, t)?;
+        let (width, height) = self.size();
+        save_png(path, &rgba, width, height)
+    }
+
+    // Renders `sheet.frames` evenly spaced frames and tiles them into one
+    // image, saved as PNG or JPEG depending on `path`'s extension.
+    fn render_contact_sheet(
+        &mut self,
+        timeline: &Timeline,
+        sheet: &ContactSheet,
+        path: &Path,
+    ) -> Result<()> {
+        sheet.validate()?;
+        let (width, height) = self.size();
+        let mut frames = Vec::with_capacity(sheet.frames as usize);
+        for t in sheet.times(timeline) {
+            frames.push(flip_rows(&self.render_frame_rgba(timeline, t)?, width));
+        }
+        save_sheet(sheet.compose(&frames, width, height)?, path)
+    }
+
+    // Writes `<dir>/frame_NNNNN.<ext>` for every frame in the range, numbered
+    // by timeline frame index. Returns the number of frames written.
+    fn render_image_sequence(
+        &mut self,
+        timeline: &Timeline,
+        start_time: f32,
+        end_time: f32,
+        dir: &Path,
+        format: SequenceFormat,
+    ) -> Result<u32>
+    where
+        Self: Sized,
+    {
+        let (width, height) = self.size();
+        let first = (start_time * timeline.fps as f32).round() as u32;
+        let mut writer = ImageSequenceWriter::start(width, height, dir, "frame")?
+            .with_format(format)
+            .with_first_number(first);
+        let options = PipelineOptions::default();
+        self.render_timeline_pipelined(timeline, start_time, end_time, &options, &mut writer)?;
+        writer.finish()
+    }
+
+    fn render_timeline_rgba(
+        &mut self,
+        timeline: &Timeline,
+        start_time: f32,
+        end_time: f32,
+        mut on_frame: impl FnMut(f32, &[u8]) -> Result<()>,
+    ) -> Result<()>
+    where
+        Self: Sized,
+    {
+        self.render_timeline_rgba_with_progress(
+            timeline,
+            start_time,
+            end_time,
+            None,
+            |t, rgba| on_frame(t, rgba),
+        )
+    }
+
+    fn render_timeline_rgba_with_progress(
+        &mut self,
+        timeline: &Timeline,
+        start_time: f32,
+        end_time: f32,
+        progress: Option<RenderProgress>,
+        on_frame: impl FnMut(f32, &[u8]) -> Result<()>,
+    ) -> Result<()>
+    where
+        Self: Sized,
+    {
+        render_frames(
+            timeline,
+            start_time,
+            end_time,
+            progress,
+            |scene| self.render_scene_to_rgba(scene),
+            on_frame,
+        )
+    }
+
+    // Same frames as `render_timeline_rgba`, but sampling runs ahead on worker
+    // threads and `sink` is fed from its own thread through a bounded queue.
+    // Drawing stays on the calling thread.
+    fn render_timeline_pipelined(
+        &mut self,
+        timeline: &Timeline,
+        start_time: f32,
+        end_time: f32,
+        options: &PipelineOptions,
+        sink: &mut (impl FrameSink + Send),
+    ) -> Result<RenderStats>
+    where
+        Self: Sized,
+    {
+        render_pipelined(
+            timeline,
+            start_time,
+            end_time,
+            options,
+            |scene| self.render_scene_to_rgba(scene),
+            sink,
+        )
+    }
+}
+
+// Lets `Box<dyn Renderer>` pick a backend at runtime and still use the
+// timeline helpers above.
+impl<R: Renderer + ?Sized> Renderer for Box<R> {
+    fn size(&self) -> (u32, u32) {
+        (**self).size()
+    }
+
+    fn preload_for_scene(&mut self, scene: &SampledScene) -> Result<()> {
+        (**self).preload_for_scene(scene)
+    }
+
+    fn clear_resources(&mut self) {
+        (**self).clear_resources()
+    }
+
+    fn render_scene_to_rgba(&mut self, scene: &SampledScene) -> Result<Vec<u8>> {
+        (**self).render_scene_to_rgba(scene)
+    }
+
+    fn measure_text(&mut self, text: &TextObject) -> Result<TextMetrics> {
+        (**self).measure_text(text)
+    }
+}
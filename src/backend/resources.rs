@@ -4,12 +4,15 @@ use std::path::{Path, PathBuf};
 use anyhow::{bail, Context, Result};
 use raylib::prelude::*;
 
+use crate::backend::text_layout::DEFAULT_ASCENT_RATIO;
 use crate::scene::{FontFamily, FontSource, Object, StyleFlags};
 use crate::timeline::SampledScene;
 
 pub struct ResourceCache {
     textures: HashMap<PathBuf, Texture2D>,
     fonts: HashMap<PathBuf, Font>,
+    // Ascent as a fraction of `font_size` for each loaded font.
+    ascents: HashMap<PathBuf, f32>,
     default_font: Option<WeakFont>,
 }
 
@@ -18,6 +21,7 @@ impl ResourceCache {
         Self {
             textures: HashMap::new(),
             fonts: HashMap::new(),
+            ascents: HashMap::new(),
             default_font: None,
         }
     }
@@ -26,6 +30,12 @@ impl ResourceCache {
         self.default_font = Some(rl.get_font_default());
     }
 
+    pub fn clear(&mut self) {
+        self.textures.clear();
+        self.fonts.clear();
+        self.ascents.clear();
+    }
+
     pub fn get_texture(&self, path: &Path) -> Result<&Texture2D> {
         if !path.exists() {
             bail!("image asset not found: {}", path.display());
@@ -83,6 +93,7 @@ impl ResourceCache {
                         .load_font(thread, path.to_string_lossy().as_ref())
                         .context("failed to load font")?;
                     self.fonts.insert(path.clone(), font);
+                    self.ascents.insert(path.clone(), ascent_ratio(path));
                 }
             }
         }
@@ -104,6 +115,28 @@ impl ResourceCache {
             }
         }
     }
+
+    // Distance from the top of a line to its baseline at `font_size`.
+    pub fn ascent(&self, family: &FontFamily, style: StyleFlags, font_size: f32) -> f32 {
+        let ratio = match family.resolve(style) {
+            FontSource::Default => None,
+            FontSource::Path(path) => self.ascents.get(path).copied(),
+        };
+        ratio.unwrap_or(DEFAULT_ASCENT_RATIO) * font_size
+    }
+}
+
+// raylib sizes TTF glyphs so that ascent - descent equals `font_size`, so the
+// baseline sits at ascent / (ascent - descent) of it. raylib's `Font` does not
+// keep the line metrics, so read them from the file with fontdue.
+fn ascent_ratio(path: &Path) -> f32 {
+    std::fs::read(path)
+        .ok()
+        .and_then(|bytes| fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default()).ok())
+        .and_then(|font| font.horizontal_line_metrics(1.0))
+        .filter(|lm| lm.ascent - lm.descent > 0.0)
+        .map(|lm| lm.ascent / (lm.ascent - lm.descent))
+        .unwrap_or(DEFAULT_ASCENT_RATIO)
 }
 
 #[derive(Clone, Copy)]
@@ -0,0 +1,781 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::backend::anchor::{anchor_offset, rotate_screen, text_anchor_offset};
+use crate::backend::renderer::Renderer;
+use crate::backend::text_layout::{layout_block, leading_style, TextMetrics, DEFAULT_ASCENT_RATIO};
+use crate::scene::{
+    Anchor, Color, FontFamily, FontSource, Object, Shape, StyleFlags, TextObject, Transform, Vec2,
+};
+use crate::timeline::SampledScene;
+
+// Fallback for `FontSource::Default` when no default font file is set.
+const BUILTIN_FONT: &[u8] = include_bytes!("../../assets/Bodoni_Moda/BodoniModa_28pt-Regular.ttf");
+
+// CPU-only renderer that mirrors `RaylibRender` without opening a window or
+// touching the GPU. Frames are returned bottom-up, like a captured raylib
+// render texture, so they drop straight into `FfmpegVideoEncoder::write_frame`.
+pub struct SoftwareRender {
+    width: u32,
+    height: u32,
+    bg: Color,
+    images: HashMap<PathBuf, RgbaImage>,
+    fonts: HashMap<PathBuf, fontdue::Font>,
+    default_font: Option<PathBuf>,
+    builtin_font: Option<fontdue::Font>,
+}
+
+impl SoftwareRender {
+    pub fn new(width: u32, height: u32, bg: Color) -> Result<Self> {
+        if width == 0 || height == 0 {
+            bail!("render size must be > 0");
+        }
+        Ok(Self {
+            width,
+            height,
+            bg,
+            images: HashMap::new(),
+            fonts: HashMap::new(),
+            default_font: None,
+            builtin_font: None,
+        })
+    }
+
+    // raylib ships a built-in bitmap font; the software backend has no
+    // equivalent, so `FontSource::Default` resolves to this file instead, or
+    // to the bundled Bodoni Moda Regular if none is set.
+    pub fn set_default_font(&mut self, path: impl Into<PathBuf>) {
+        self.default_font = Some(path.into());
+    }
+
+    fn load_image(&mut self, path: &Path) -> Result<()> {
+        if !path.exists() {
+            bail!("image asset not found: {}", path.display());
+        }
+        if !self.images.contains_key(path) {
+            let decoded = image::open(path)
+                .with_context(|| format!("failed to load image: {}", path.display()))?
+                .to_rgba8();
+            self.images.insert(
+                path.to_path_buf(),
+                RgbaImage {
+                    width: decoded.width(),
+                    height: decoded.height(),
+                    pixels: decoded.into_raw(),
+                },
+            );
+        }
+        Ok(())
+    }
+
+    fn load_font(&mut self, path: &Path) -> Result<()> {
+        if !self.fonts.contains_key(path) {
+            let bytes = std::fs::read(path)
+                .with_context(|| format!("failed to read font: {}", path.display()))?;
+            let font = fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default())
+                .map_err(|e| anyhow!("failed to load font {}: {e}", path.display()))?;
+            self.fonts.insert(path.to_path_buf(), font);
+        }
+        Ok(())
+    }
+
+    fn load_font_family(&mut self, family: &FontFamily) -> Result<()> {
+        for source in [
+            Some(&family.regular),
+            family.bold.as_ref(),
+            family.italic.as_ref(),
+            family.bold_italic.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            match self.font_path(source) {
+                Some(path) => {
+                    let path = path.to_path_buf();
+                    self.load_font(&path)?;
+                }
+                None => self.load_builtin_font()?,
+            }
+        }
+        Ok(())
+    }
+
+    fn load_builtin_font(&mut self) -> Result<()> {
+        if self.builtin_font.is_none() {
+            let font = fontdue::Font::from_bytes(BUILTIN_FONT, fontdue::FontSettings::default())
+                .map_err(|e| anyhow!("failed to load built-in font: {e}"))?;
+            self.builtin_font = Some(font);
+        }
+        Ok(())
+    }
+
+    // `None` means the built-in font.
+    fn font_path<'a>(&'a self, source: &'a FontSource) -> Option<&'a Path> {
+        match source {
+            FontSource::Path(path) => Some(path),
+            FontSource::Default => self.default_font.as_deref(),
+        }
+    }
+
+    fn resolve_font(&self, text: &TextObject, style: StyleFlags) -> Result<&fontdue::Font> {
+        match self.font_path(text.font.resolve(style)) {
+            Some(path) => self.fonts.get(path),
+            None => self.builtin_font.as_ref(),
+        }
+        .context("font not loaded")
+    }
+
+    fn draw_object(&self, canvas: &mut Canvas, object: &Object, transform: &Transform) -> Result<()> {
+        match object {
+            Object::Shape(shape) => {
+                draw_shape(canvas, shape, transform);
+                Ok(())
+            }
+            Object::Image(image) => {
+                let texture = self.images.get(&image.path).context("image not loaded")?;
+                draw_image(canvas, texture, transform);
+                Ok(())
+            }
+            Object::Text(text) => self.draw_text_block(canvas, text, transform),
+            // Flattened by `Timeline::sample`.
+            Object::Group(_) => Ok(()),
+        }
+    }
+
+    fn draw_text_block(&self, canvas: &mut Canvas, text: &TextObject, transform: &Transform) -> Result<()> {
+        let pivot = canvas.graph_to_screen(transform.pos);
+        let font_size = text.font_size * transform.scale.y.max(0.0);
+        let spacing = text.spacing;
+
+        let (_, ascent) = em_size(self.resolve_font(text, leading_style(text))?, font_size);
+        let block = layout_block(text, font_size, ascent, |style, token| {
+            let font = self.resolve_font(text, style)?;
+            Ok(measure_text(font, token, font_size, spacing))
+        })?;
+        let metrics = block.metrics;
+        let anchor = text_anchor_offset(
+            transform,
+            metrics.default_anchor(text),
+            metrics.width,
+            metrics.height,
+        );
+
+        // Every run is placed relative to the anchor so the block rotates as one.
+        let tint = apply_opacity(text.color, transform.opacity);
+        for placed in block.runs {
+            let font = self.resolve_font(text, placed.run.style)?;
+            let local = Vec2 {
+                x: placed.x - anchor.x,
+                y: placed.y - anchor.y,
+            };
+            draw_text_run(
+                canvas,
+                font,
+                &placed.run.text,
+                pivot,
+                local,
+                transform.rotation,
+                font_size,
+                spacing,
+                tint,
+            );
+
+            if placed.run.style.underline {
+                // Matches raylib's `draw_line_ex(.., 2.0, ..)`: centered on the line.
+                let underline_y = local.y + font_size * 0.9;
+                canvas.fill_quad(
+                    pivot,
+                    transform.rotation,
+                    (local.x, underline_y - 1.0, placed.width, 2.0),
+                    |_, _| tint,
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Renderer for SoftwareRender {
+    fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    fn preload_for_scene(&mut self, scene: &SampledScene) -> Result<()> {
+        for layer in &scene.layers {
+            for clip in &layer.clips {
+                match &clip.object {
+                    Object::Image(image) => self.load_image(&image.path)?,
+                    Object::Text(text) => self.load_font_family(&text.font)?,
+                    Object::Shape(_) | Object::Group(_) => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn clear_resources(&mut self) {
+        self.images.clear();
+        self.fonts.clear();
+        self.builtin_font = None;
+    }
+
+    fn measure_text(&mut self, text: &TextObject) -> Result<TextMetrics> {
+        self.load_font_family(&text.font)?;
+        let (_, ascent) = em_size(self.resolve_font(text, leading_style(text))?, text.font_size);
+        let block = layout_block(text, text.font_size, ascent, |style, token| {
+            let font = self.resolve_font(text, style)?;
+            Ok(measure_text(font, token, text.font_size, text.spacing))
+        })?;
+        Ok(block.metrics)
+    }
+
+    fn render_scene_to_rgba(&mut self, scene: &SampledScene) -> Result<Vec<u8>> {
+        self.preload_for_scene(scene)?;
+
+        let mut canvas = Canvas::new(self.width, self.height, self.bg);
+        for layer in &scene.layers {
+            for clip in &layer.clips {
+                self.draw_object(&mut canvas, &clip.object, &clip.transform)?;
+            }
+        }
+
+        Ok(canvas.into_bottom_up_rgba())
+    }
+}
+
+struct RgbaImage {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+// Straight-alpha RGBA canvas in screen space (top-left origin, +y down).
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<f32>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32, bg: Color) -> Self {
+        let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+        for _ in 0..width * height {
+            pixels.extend_from_slice(&[
+                bg.r as f32 / 255.0,
+                bg.g as f32 / 255.0,
+                bg.b as f32 / 255.0,
+                bg.a as f32 / 255.0,
+            ]);
+        }
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn graph_to_screen(&self, pos: Vec2) -> Vec2 {
+        Vec2 {
+            x: self.width as f32 / 2.0 + pos.x,
+            y: self.height as f32 / 2.0 - pos.y,
+        }
+    }
+
+    fn blend(&mut self, x: i64, y: i64, src: [f32; 4]) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 || src[3] <= 0.0 {
+            return;
+        }
+        let idx = ((y as u32 * self.width + x as u32) * 4) as usize;
+        let dst = &mut self.pixels[idx..idx + 4];
+        // Straight-alpha "over"; reduces to a plain mix on an opaque canvas.
+        let a = src[3].min(1.0);
+        let below = dst[3] * (1.0 - a);
+        let out = a + below;
+        for c in 0..3 {
+            dst[c] = (src[c] * a + dst[c] * below) / out;
+        }
+        dst[3] = out;
+    }
+
+    // Fills the rectangle `(x, y, w, h)` given in local space around `pivot`,
+    // rotated by `rotation` degrees clockwise on screen (raylib's convention).
+    // `sample` receives normalized (u, v) in 0..1 and returns straight RGBA.
+    fn fill_quad(
+        &mut self,
+        pivot: Vec2,
+        rotation: f32,
+        rect: (f32, f32, f32, f32),
+        sample: impl Fn(f32, f32) -> [f32; 4],
+    ) {
+        let (rx, ry, rw, rh) = rect;
+        if rw <= 0.0 || rh <= 0.0 {
+            return;
+        }
+        let (sin, cos) = rotation.to_radians().sin_cos();
+        let corners = [(rx, ry), (rx + rw, ry), (rx, ry + rh), (rx + rw, ry + rh)];
+        let mut min_x = f32::INFINITY;
+        let mut min_y = f32::INFINITY;
+        let mut max_x = f32::NEG_INFINITY;
+        let mut max_y = f32::NEG_INFINITY;
+        for (lx, ly) in corners {
+            let sx = pivot.x + lx * cos - ly * sin;
+            let sy = pivot.y + lx * sin + ly * cos;
+            min_x = min_x.min(sx);
+            min_y = min_y.min(sy);
+            max_x = max_x.max(sx);
+            max_y = max_y.max(sy);
+        }
+
+        let x0 = min_x.floor().max(0.0) as i64;
+        let y0 = min_y.floor().max(0.0) as i64;
+        let x1 = max_x.ceil().min(self.width as f32) as i64;
+        let y1 = max_y.ceil().min(self.height as f32) as i64;
+
+        for py in y0..y1 {
+            for px in x0..x1 {
+                let dx = px as f32 + 0.5 - pivot.x;
+                let dy = py as f32 + 0.5 - pivot.y;
+                let lx = dx * cos + dy * sin;
+                let ly = -dx * sin + dy * cos;
+                let u = (lx - rx) / rw;
+                let v = (ly - ry) / rh;
+                if (0.0..1.0).contains(&u) && (0.0..1.0).contains(&v) {
+                    self.blend(px, py, sample(u, v));
+                }
+            }
+        }
+    }
+
+    fn fill_circle(&mut self, center: Vec2, radius: f32, color: [f32; 4]) {
+        if radius <= 0.0 {
+            return;
+        }
+        let x0 = (center.x - radius).floor().max(0.0) as i64;
+        let y0 = (center.y - radius).floor().max(0.0) as i64;
+        let x1 = (center.x + radius).ceil().min(self.width as f32) as i64;
+        let y1 = (center.y + radius).ceil().min(self.height as f32) as i64;
+
+        for py in y0..y1 {
+            for px in x0..x1 {
+                let dx = px as f32 + 0.5 - center.x;
+                let dy = py as f32 + 0.5 - center.y;
+                let coverage = (radius - (dx * dx + dy * dy).sqrt() + 0.5).clamp(0.0, 1.0);
+                if coverage > 0.0 {
+                    self.blend(px, py, [color[0], color[1], color[2], color[3] * coverage]);
+                }
+            }
+        }
+    }
+
+    // raylib render textures are read back bottom-up; flip to match so callers
+    // (and the encoder's `vflip`) see identical frames from either backend.
+    fn into_bottom_up_rgba(self) -> Vec<u8> {
+        let row = (self.width * 4) as usize;
+        let mut out = Vec::with_capacity(self.pixels.len());
+        for y in (0..self.height as usize).rev() {
+            for value in &self.pixels[y * row..(y + 1) * row] {
+                out.push((value.clamp(0.0, 1.0) * 255.0).round() as u8);
+            }
+        }
+        out
+    }
+}
+
+fn draw_shape(canvas: &mut Canvas, shape: &Shape, transform: &Transform) {
+    let pivot = canvas.graph_to_screen(transform.pos);
+    let color = apply_opacity(
+        match shape {
+            Shape::Circle { color, .. } => *color,
+            Shape::Rect { color, .. } => *color,
+        },
+        transform.opacity,
+    );
+
+    match shape {
+        Shape::Circle { radius, .. } => {
+            let scale = transform.scale.x.max(0.0);
+            let scaled = radius * scale;
+            let uniform = Transform {
+                scale: Vec2 { x: scale, y: scale },
+                ..*transform
+            };
+            let anchor = anchor_offset(&uniform, Anchor::CENTER, radius * 2.0, radius * 2.0);
+            let offset = rotate_screen(
+                Vec2 {
+                    x: scaled - anchor.x,
+                    y: scaled - anchor.y,
+                },
+                transform.rotation,
+            );
+            let center = Vec2 {
+                x: pivot.x + offset.x,
+                y: pivot.y + offset.y,
+            };
+            canvas.fill_circle(center, scaled, color);
+        }
+        Shape::Rect { width: w, height: h, .. } => {
+            let anchor = anchor_offset(transform, Anchor::CENTER, *w, *h);
+            let w = w * transform.scale.x;
+            let h = h * transform.scale.y;
+            canvas.fill_quad(pivot, transform.rotation, (-anchor.x, -anchor.y, w, h), |_, _| color);
+        }
+    }
+}
+
+fn draw_image(canvas: &mut Canvas, texture: &RgbaImage, transform: &Transform) {
+    let w = texture.width as f32 * transform.scale.x;
+    let h = texture.height as f32 * transform.scale.y;
+    let pivot = canvas.graph_to_screen(transform.pos);
+    let anchor = anchor_offset(transform, Anchor::CENTER, texture.width as f32, texture.height as f32);
+    let tint = apply_opacity(Color::WHITE, transform.opacity);
+
+    // Nearest-neighbour sampling, matching raylib's default texture filter.
+    canvas.fill_quad(pivot, transform.rotation, (-anchor.x, -anchor.y, w, h), |u, v| {
+        let tx = ((u * texture.width as f32) as u32).min(texture.width - 1);
+        let ty = ((v * texture.height as f32) as u32).min(texture.height - 1);
+        let idx = ((ty * texture.width + tx) * 4) as usize;
+        let px = &texture.pixels[idx..idx + 4];
+        [
+            px[0] as f32 / 255.0 * tint[0],
+            px[1] as f32 / 255.0 * tint[1],
+            px[2] as f32 / 255.0 * tint[2],
+            px[3] as f32 / 255.0 * tint[3],
+        ]
+    });
+}
+
+// raylib sizes TTF glyphs so that ascent - descent equals `font_size`;
+// fontdue scales by the em box, so convert between the two.
+fn em_size(font: &fontdue::Font, font_size: f32) -> (f32, f32) {
+    match font.horizontal_line_metrics(1.0) {
+        Some(lm) if lm.ascent - lm.descent > 0.0 => {
+            let px = font_size / (lm.ascent - lm.descent);
+            (px, lm.ascent * px)
+        }
+        _ => (font_size, font_size * DEFAULT_ASCENT_RATIO),
+    }
+}
+
+fn measure_text(font: &fontdue::Font, text: &str, font_size: f32, spacing: f32) -> f32 {
+    let (px, _) = em_size(font, font_size);
+    let mut width = 0.0;
+    let mut count = 0;
+    for ch in text.chars() {
+        width += font.metrics(ch, px).advance_width;
+        count += 1;
+    }
+    if count > 1 {
+        width += (count - 1) as f32 * spacing;
+    }
+    width
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_text_run(
+    canvas: &mut Canvas,
+    font: &fontdue::Font,
+    text: &str,
+    pivot: Vec2,
+    offset: Vec2,
+    rotation: f32,
+    font_size: f32,
+    spacing: f32,
+    tint: [f32; 4],
+) {
+    let (px, ascent) = em_size(font, font_size);
+    let mut pen_x = 0.0;
+    for ch in text.chars() {
+        let (metrics, coverage) = font.rasterize(ch, px);
+        if metrics.width > 0 && metrics.height > 0 {
+            let gx = pen_x + metrics.xmin as f32;
+            let gy = ascent - (metrics.ymin as f32 + metrics.height as f32);
+            let gw = metrics.width;
+            let gh = metrics.height;
+            canvas.fill_quad(
+                pivot,
+                rotation,
+                (offset.x + gx, offset.y + gy, gw as f32, gh as f32),
+                |u, v| {
+                    let cx = ((u * gw as f32) as usize).min(gw - 1);
+                    let cy = ((v * gh as f32) as usize).min(gh - 1);
+                    let alpha = coverage[cy * gw + cx] as f32 / 255.0;
+                    [tint[0], tint[1], tint[2], tint[3] * alpha]
+                },
+            );
+        }
+        pen_x += metrics.advance_width + spacing;
+    }
+}
+
+fn apply_opacity(color: Color, opacity: f32) -> [f32; 4] {
+    [
+        color.r as f32 / 255.0,
+        color.g as f32 / 255.0,
+        color.b as f32 / 255.0,
+        color.a as f32 / 255.0 * opacity.clamp(0.0, 1.0),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{ImageObject, StyledText, TextAlign, VerticalAlign};
+    use crate::timeline::{SampledClip, SampledLayer};
+
+    const W: u32 = 100;
+    const H: u32 = 100;
+    const BODONI: &str = "assets/Bodoni_Moda/BodoniModa_28pt-Regular.ttf";
+    const BODONI_BOLD: &str = "assets/Bodoni_Moda/BodoniModa_28pt-Bold.ttf";
+
+    fn scene(objects: Vec<(Object, Transform)>) -> SampledScene {
+        SampledScene {
+            layers: vec![SampledLayer {
+                name: "test".to_string(),
+                clips: objects
+                    .into_iter()
+                    .map(|(object, transform)| SampledClip { object, transform })
+                    .collect(),
+            }],
+        }
+    }
+
+    fn render(objects: Vec<(Object, Transform)>) -> Vec<u8> {
+        let mut renderer = SoftwareRender::new(W, H, Color::BLACK).unwrap();
+        renderer.render_scene_to_rgba(&scene(objects)).unwrap()
+    }
+
+    // Reads screen pixel (x, y), top-left origin, from a bottom-up frame.
+    fn pixel(frame: &[u8], x: u32, y: u32) -> [u8; 4] {
+        let idx = (((H - 1 - y) * W + x) * 4) as usize;
+        frame[idx..idx + 4].try_into().unwrap()
+    }
+
+    fn at(x: f32, y: f32) -> Transform {
+        Transform {
+            pos: Vec2 { x, y },
+            ..Transform::default()
+        }
+    }
+
+    fn rect(width: f32, height: f32, color: Color) -> Object {
+        Object::Shape(Shape::Rect {
+            width,
+            height,
+            color,
+        })
+    }
+
+    fn text(markdown: &str) -> TextObject {
+        TextObject {
+            text: StyledText::from_markdown(markdown),
+            font: FontFamily {
+                regular: FontSource::Path(BODONI.into()),
+                bold: Some(FontSource::Path(BODONI_BOLD.into())),
+                italic: None,
+                bold_italic: None,
+            },
+            font_size: 30.0,
+            spacing: 0.0,
+            max_width: 0.0,
+            color: Color::rgb(255, 0, 0),
+            line_spacing: 1.0,
+            align: TextAlign::Left,
+            vertical_align: VerticalAlign::Top,
+        }
+    }
+
+    // Screen pixels with any ink on them.
+    fn inked(frame: &[u8]) -> Vec<(u32, u32)> {
+        let mut out = Vec::new();
+        for y in 0..H {
+            for x in 0..W {
+                if pixel(frame, x, y)[..3] != [0, 0, 0] {
+                    out.push((x, y));
+                }
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn frames_are_bottom_up() {
+        // A bar across the top of the screen ends up in the last rows.
+        let frame = render(vec![(rect(W as f32, 10.0, Color::WHITE), at(0.0, 45.0))]);
+        let row = (W * 4) as usize;
+        assert_eq!(frame.len(), (W * H * 4) as usize);
+        assert_eq!(frame[..4], [0, 0, 0, 255]);
+        assert_eq!(frame[(H as usize - 1) * row..][..4], [255, 255, 255, 255]);
+        assert_eq!(frame[(H as usize - 11) * row..][..4], [0, 0, 0, 255]);
+        assert_eq!(pixel(&frame, 50, 2), [255, 255, 255, 255]);
+        assert_eq!(pixel(&frame, 50, 97), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn draws_circles() {
+        let circle = Object::Shape(Shape::Circle {
+            radius: 10.0,
+            color: Color::rgb(0, 255, 0),
+        });
+        let frame = render(vec![(circle, at(20.0, 0.0))]);
+        assert_eq!(pixel(&frame, 70, 50), [0, 255, 0, 255]);
+        assert_eq!(pixel(&frame, 77, 50), [0, 255, 0, 255]);
+        assert_eq!(pixel(&frame, 82, 50), [0, 0, 0, 255]);
+        // Corner of the bounding box is outside the circle.
+        assert_eq!(pixel(&frame, 78, 42), [0, 0, 0, 255]);
+        // Edges are antialiased.
+        let edge = pixel(&frame, 79, 50)[1];
+        assert!(edge > 0 && edge < 255, "edge = {edge}");
+    }
+
+    #[test]
+    fn rotates_rects_clockwise_around_their_center() {
+        let transform = Transform {
+            rotation: 90.0,
+            ..Transform::default()
+        };
+        let frame = render(vec![(rect(60.0, 10.0, Color::WHITE), transform)]);
+        // Now a vertical bar through the center.
+        assert_eq!(pixel(&frame, 50, 25), [255, 255, 255, 255]);
+        assert_eq!(pixel(&frame, 50, 75), [255, 255, 255, 255]);
+        assert_eq!(pixel(&frame, 25, 50), [0, 0, 0, 255]);
+        assert_eq!(pixel(&frame, 75, 50), [0, 0, 0, 255]);
+
+        // A 45 degree turn counter-clockwise on screen lifts the right end.
+        let transform = Transform {
+            rotation: -45.0,
+            ..Transform::default()
+        };
+        let frame = render(vec![(rect(60.0, 4.0, Color::WHITE), transform)]);
+        assert_eq!(pixel(&frame, 65, 35), [255, 255, 255, 255]);
+        assert_eq!(pixel(&frame, 35, 65), [255, 255, 255, 255]);
+        assert_eq!(pixel(&frame, 65, 65), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn draws_images_upright_with_nearest_sampling() {
+        let dir = std::env::temp_dir().join(format!("s2s-software-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("quadrants.png");
+        // Red, green / blue, white.
+        let quadrants = image::RgbaImage::from_fn(2, 2, |x, y| match (x, y) {
+            (0, 0) => image::Rgba([255, 0, 0, 255]),
+            (1, 0) => image::Rgba([0, 255, 0, 255]),
+            (0, 1) => image::Rgba([0, 0, 255, 255]),
+            _ => image::Rgba([255, 255, 255, 255]),
+        });
+        quadrants.save(&path).unwrap();
+
+        let transform = Transform {
+            scale: Vec2 { x: 20.0, y: 20.0 },
+            ..Transform::default()
+        };
+        let frame = render(vec![(Object::Image(ImageObject::new(&path)), transform)]);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(pixel(&frame, 45, 45), [255, 0, 0, 255]);
+        assert_eq!(pixel(&frame, 55, 45), [0, 255, 0, 255]);
+        assert_eq!(pixel(&frame, 45, 55), [0, 0, 255, 255]);
+        assert_eq!(pixel(&frame, 55, 55), [255, 255, 255, 255]);
+        assert_eq!(pixel(&frame, 31, 31), [255, 0, 0, 255]);
+        assert_eq!(pixel(&frame, 28, 28), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn opacity_blends_over_the_background() {
+        let half = Transform {
+            opacity: 0.5,
+            ..Transform::default()
+        };
+        let translucent = Color::rgba(255, 255, 255, 128);
+        let frame = render(vec![
+            (rect(20.0, 20.0, Color::WHITE), half),
+            (rect(20.0, 20.0, translucent), at(30.0, 0.0)),
+        ]);
+        assert_eq!(pixel(&frame, 50, 50), [128, 128, 128, 255]);
+        assert_eq!(pixel(&frame, 80, 50), [128, 128, 128, 255]);
+
+        // On a transparent canvas the alpha survives.
+        let mut renderer = SoftwareRender::new(W, H, Color::TRANSPARENT).unwrap();
+        let frame = renderer
+            .render_scene_to_rgba(&scene(vec![(rect(20.0, 20.0, Color::WHITE), half)]))
+            .unwrap();
+        assert_eq!(pixel(&frame, 50, 50), [255, 255, 255, 128]);
+    }
+
+    #[test]
+    fn draws_styled_text_from_the_top_left() {
+        let frame = render(vec![(Object::Text(text("Il")), at(-40.0, 40.0))]);
+        let ink = inked(&frame);
+        assert!(!ink.is_empty());
+        // Top-left anchored at screen (10, 10), tinted with the text color.
+        assert!(ink.iter().all(|&(x, y)| x >= 10 && y >= 10));
+        assert!(ink.iter().any(|&(_, y)| y < 20));
+        assert!(ink.iter().all(|&(x, y)| {
+            let [r, g, b, _] = pixel(&frame, x, y);
+            r > 0 && g == 0 && b == 0
+        }));
+
+        // Bold runs use the bold face, which has heavier strokes.
+        let bold = render(vec![(Object::Text(text("**Il**")), at(-40.0, 40.0))]);
+        assert!(inked(&bold).len() > ink.len());
+
+        // Underline runs get a 2 px rule under the glyphs.
+        let underlined = render(vec![(Object::Text(text("__Il__")), at(-40.0, 40.0))]);
+        let rule_y = 10 + (30.0_f32 * 0.9) as u32;
+        assert_eq!(pixel(&underlined, 11, rule_y), [255, 0, 0, 255]);
+        assert_eq!(pixel(&frame, 11, rule_y), [0, 0, 0, 255]);
+
+        let faded = Transform {
+            opacity: 0.5,
+            ..at(-40.0, 40.0)
+        };
+        let faded = render(vec![(Object::Text(text("__Il__")), faded)]);
+        assert_eq!(pixel(&faded, 11, rule_y), [128, 0, 0, 255]);
+    }
+
+    #[test]
+    fn measures_text_with_the_font_ascent() {
+        let mut renderer = SoftwareRender::new(W, H, Color::BLACK).unwrap();
+        let one = renderer.measure_text(&text("Il")).unwrap();
+        let two = renderer.measure_text(&text("Il\nIl")).unwrap();
+        assert_eq!((one.line_count, two.line_count), (1, 2));
+        assert_eq!(one.width, two.width);
+        assert_eq!(one.height, 30.0);
+        // `line_spacing` is 1.
+        assert_eq!(two.height, 30.0 + 1.0 + 30.0);
+
+        let bytes = std::fs::read(BODONI).unwrap();
+        let font = fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default()).unwrap();
+        let lm = font.horizontal_line_metrics(1.0).unwrap();
+        let ascent = 30.0 * lm.ascent / (lm.ascent - lm.descent);
+        assert!((one.baseline - ascent).abs() < 1e-3, "{}", one.baseline);
+        assert_ne!(one.baseline, 30.0 * DEFAULT_ASCENT_RATIO);
+
+        // Doubling the font size doubles the block.
+        let mut big = text("Il");
+        big.font_size = 60.0;
+        let big = renderer.measure_text(&big).unwrap();
+        assert!((big.width - 2.0 * one.width).abs() < 1.0);
+        assert!((big.baseline - 2.0 * one.baseline).abs() < 1e-3);
+    }
+
+    #[test]
+    fn default_font_falls_back_to_the_bundled_face() {
+        let mut object = text("Hi");
+        object.font = FontFamily::default();
+        let mut renderer = SoftwareRender::new(W, H, Color::BLACK).unwrap();
+        let builtin = renderer.measure_text(&object).unwrap();
+        assert!(builtin.width > 0.0);
+
+        // Same face as the bundled one, so the same metrics.
+        renderer.set_default_font(BODONI);
+        assert_eq!(renderer.measure_text(&object).unwrap(), builtin);
+        let frame = renderer
+            .render_scene_to_rgba(&scene(vec![(Object::Text(object), at(-40.0, 40.0))]))
+            .unwrap();
+        assert!(!inked(&frame).is_empty());
+    }
+}
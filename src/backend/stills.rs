@@ -0,0 +1,168 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+
+use crate::encoder::flip_rows;
+use crate::scene::Color;
+use crate::timeline::Timeline;
+
+// A grid of `frames` thumbnails taken at evenly spaced times across the
+// timeline, `columns` wide, for review sheets and thumbnails.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContactSheet {
+    pub frames: u32,
+    pub columns: u32,
+    // Thumbnail width in pixels; height follows the render aspect ratio.
+    pub thumb_width: u32,
+    // Gap between thumbnails and around the edge, in pixels.
+    pub spacing: u32,
+    pub background: Color,
+}
+
+impl ContactSheet {
+    pub fn new(frames: u32, columns: u32) -> Self {
+        Self {
+            frames,
+            columns,
+            thumb_width: 320,
+            spacing: 8,
+            background: Color::rgb(24, 24, 28),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.frames == 0 || self.columns == 0 || self.thumb_width == 0 {
+            bail!("contact sheet frames, columns and thumb_width must be > 0");
+        }
+        Ok(())
+    }
+
+    // The middle of each of `frames` equal spans, snapped down to the frame
+    // grid so every thumbnail matches a frame of the rendered video.
+    pub fn times(&self, timeline: &Timeline) -> Vec<f32> {
+        let fps = timeline.fps as f32;
+        (0..self.frames)
+            .map(|i| {
+                let t = (i as f32 + 0.5) / self.frames as f32 * timeline.duration;
+                (t * fps).floor() / fps
+            })
+            .collect()
+    }
+
+    // Tiles top-down RGBA frames of `width` x `height` into the sheet.
+    pub(crate) fn compose(&self, frames: &[Vec<u8>], width: u32, height: u32) -> Result<RgbaImage> {
+        let thumb_height = ((self.thumb_width as u64 * height as u64) / width as u64).max(1) as u32;
+        let rows = (frames.len() as u32).div_ceil(self.columns);
+        let cols = self.columns.min(frames.len() as u32);
+        let sheet_width = cols * self.thumb_width + (cols + 1) * self.spacing;
+        let sheet_height = rows * thumb_height + (rows + 1) * self.spacing;
+        let bg = image::Rgba([
+            self.background.r,
+            self.background.g,
+            self.background.b,
+            self.background.a,
+        ]);
+        let mut sheet = RgbaImage::from_pixel(sheet_width, sheet_height, bg);
+
+        for (idx, rgba) in frames.iter().enumerate() {
+            let frame = RgbaImage::from_raw(width, height, rgba.clone())
+                .context("frame size does not match the renderer")?;
+            let thumb = imageops::resize(&frame, self.thumb_width, thumb_height, FilterType::Triangle);
+            let (col, row) = (idx as u32 % self.columns, idx as u32 / self.columns);
+            let x = self.spacing + col * (self.thumb_width + self.spacing);
+            let y = self.spacing + row * (thumb_height + self.spacing);
+            imageops::overlay(&mut sheet, &thumb, x as i64, y as i64);
+        }
+        Ok(sheet)
+    }
+}
+
+// PNG keeps the alpha channel; JPEG has none, so it's dropped.
+pub(crate) fn save_sheet(sheet: RgbaImage, path: &Path) -> Result<()> {
+    let jpeg = image::ImageFormat::from_path(path).is_ok_and(|f| f == image::ImageFormat::Jpeg);
+    let saved = if jpeg {
+        image::DynamicImage::ImageRgba8(sheet).to_rgb8().save(path)
+    } else {
+        sheet.save(path)
+    };
+    saved.with_context(|| format!("failed to write {}", path.display()))
+}
+
+// Saves a bottom-up renderer frame as a PNG.
+pub(crate) fn save_png(path: &Path, rgba: &[u8], width: u32, height: u32) -> Result<()> {
+    image::save_buffer_with_format(
+        path,
+        &flip_rows(rgba, width),
+        width,
+        height,
+        image::ExtendedColorType::Rgba8,
+        image::ImageFormat::Png,
+    )
+    .with_context(|| format!("failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::renderer::Renderer;
+    use crate::backend::software::SoftwareRender;
+    use crate::encoder::SequenceFormat;
+    use crate::scene::{AnimatedTransform, Object, Shape};
+    use crate::timeline::{Clip, Layer};
+
+    // A white square over a black background for the second half of the
+    // timeline.
+    fn timeline() -> Timeline {
+        let mut timeline = Timeline::new(2.0, 10).unwrap();
+        let mut layer = Layer::new("square");
+        let square = Object::Shape(Shape::Rect {
+            width: 40.0,
+            height: 40.0,
+            color: Color::WHITE,
+        });
+        layer.add_clip(Clip::new(1.0, 2.0, square, AnimatedTransform::default(), 2.0).unwrap());
+        timeline.add_layer(layer);
+        timeline
+    }
+
+    #[test]
+    fn renders_stills_and_contact_sheets() {
+        let timeline = timeline();
+        let mut renderer = SoftwareRender::new(80, 60, Color::BLACK).unwrap();
+        let dir = std::env::temp_dir().join(format!("s2s-stills-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let still = dir.join("still.png");
+        renderer.render_frame_png(&timeline, 1.5, &still).unwrap();
+        let png = image::open(&still).unwrap().to_rgba8();
+        assert_eq!(png.dimensions(), (80, 60));
+        assert_eq!(png.get_pixel(40, 30).0, [255, 255, 255, 255]);
+        assert_eq!(png.get_pixel(2, 2).0, [0, 0, 0, 255]);
+
+        let sheet = ContactSheet {
+            thumb_width: 40,
+            spacing: 4,
+            ..ContactSheet::new(4, 2)
+        };
+        assert_eq!(sheet.times(&timeline), [0.2, 0.7, 1.2, 1.7]);
+        let path = dir.join("sheet.png");
+        renderer.render_contact_sheet(&timeline, &sheet, &path).unwrap();
+        let png = image::open(&path).unwrap().to_rgba8();
+        // 2x2 thumbnails of 40x30 with 4 px gaps.
+        assert_eq!(png.dimensions(), (92, 72));
+        // Top row is before the square appears, bottom row after.
+        assert_eq!(png.get_pixel(24, 19).0, [0, 0, 0, 255]);
+        assert_eq!(png.get_pixel(24, 53).0, [255, 255, 255, 255]);
+        assert_eq!(png.get_pixel(1, 1).0, [24, 24, 28, 255]);
+
+        let frames = renderer
+            .render_image_sequence(&timeline, 1.0, 1.5, &dir, SequenceFormat::Exr)
+            .unwrap();
+        assert_eq!(frames, 5);
+        assert!(dir.join("frame_00010.exr").exists());
+        assert!(dir.join("frame_00014.exr").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
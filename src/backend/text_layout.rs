@@ -0,0 +1,472 @@
+use anyhow::Result;
+
+use crate::scene::{Anchor, StyleFlags, TextAlign, TextObject, TextRun, VerticalAlign};
+
+pub struct LineLayout {
+    pub runs: Vec<TextRun>,
+    // Last line before an explicit newline or the end of the text.
+    pub ends_paragraph: bool,
+}
+
+// A run placed relative to the top-left corner of its text block.
+pub struct PlacedRun {
+    pub run: TextRun,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+}
+
+pub struct TextBlock {
+    pub runs: Vec<PlacedRun>,
+    pub metrics: TextMetrics,
+}
+
+// Laid-out size of a text block at a given font size, before any transform.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextMetrics {
+    pub width: f32,
+    pub height: f32,
+    // Distance from the top of the block to the first line's baseline.
+    pub baseline: f32,
+    pub line_count: usize,
+}
+
+impl TextMetrics {
+    // Block point that sits at the transform position when no explicit
+    // anchor is set, from the text's alignment settings.
+    pub fn default_anchor(&self, text: &TextObject) -> Anchor {
+        let x = match text.align {
+            TextAlign::Left | TextAlign::Justify => 0.0,
+            TextAlign::Center => 0.5,
+            TextAlign::Right => 1.0,
+        };
+        let y = match text.vertical_align {
+            VerticalAlign::Top => 0.0,
+            VerticalAlign::Middle => 0.5,
+            VerticalAlign::Bottom => 1.0,
+            VerticalAlign::Baseline if self.height > 0.0 => self.baseline / self.height,
+            VerticalAlign::Baseline => 0.0,
+        };
+        Anchor::normalized(x, y)
+    }
+}
+
+// Baseline ratio for fonts without usable line metrics, such as raylib's
+// bitmap default font.
+pub const DEFAULT_ASCENT_RATIO: f32 = 0.8;
+
+// Style of the first run, whose font sets the first line's baseline.
+pub fn leading_style(text: &TextObject) -> StyleFlags {
+    text.text
+        .runs
+        .first()
+        .map(|run| run.style)
+        .unwrap_or(StyleFlags::PLAIN)
+}
+
+// Wraps `text`, aligns every line and places each run. `font_size` must match
+// the size `measure` uses; lines are `font_size + text.line_spacing` apart.
+// `ascent` is the distance from the top of a line to its baseline at
+// `font_size` for the font of `leading_style(text)`.
+pub fn layout_block(
+    text: &TextObject,
+    font_size: f32,
+    ascent: f32,
+    mut measure: impl FnMut(StyleFlags, &str) -> Result<f32>,
+) -> Result<TextBlock> {
+    let mut lines = layout_lines(text, &mut measure)?;
+    let line_height = font_size + text.line_spacing;
+
+    // Trailing spaces would push right/center aligned lines off their edge.
+    let mut widths = Vec::with_capacity(lines.len());
+    for line in &mut lines {
+        trim_trailing_whitespace(&mut line.runs);
+        let mut width = 0.0;
+        for run in &line.runs {
+            width += measure(run.style, &run.text)?;
+        }
+        widths.push(width);
+    }
+    let block_width = widths.iter().copied().fold(0.0, f32::max);
+
+    let line_count = lines.len();
+    let mut runs = Vec::new();
+    for (idx, (line, line_width)) in lines.into_iter().zip(widths).enumerate() {
+        let y = idx as f32 * line_height;
+        let slack = block_width - line_width;
+        let (mut x, gap_extra) = match text.align {
+            TextAlign::Left => (0.0, 0.0),
+            TextAlign::Center => (slack / 2.0, 0.0),
+            TextAlign::Right => (slack, 0.0),
+            TextAlign::Justify => {
+                let gaps = count_gaps(&line.runs);
+                if line.ends_paragraph || gaps == 0 {
+                    (0.0, 0.0)
+                } else {
+                    (0.0, slack / gaps as f32)
+                }
+            }
+        };
+
+        // Leading whitespace keeps its width; `count_gaps` skips it too.
+        let mut seen_word = false;
+        for run in line.runs {
+            if gap_extra > 0.0 {
+                // Place word by word so each gap can grow.
+                for token in split_tokens(&run.text) {
+                    let mut width = measure(run.style, &token)?;
+                    if token.trim().is_empty() {
+                        if seen_word {
+                            width += gap_extra;
+                        }
+                    } else {
+                        seen_word = true;
+                    }
+                    runs.push(PlacedRun {
+                        run: TextRun {
+                            text: token,
+                            style: run.style,
+                        },
+                        x,
+                        y,
+                        width,
+                    });
+                    x += width;
+                }
+            } else {
+                let width = measure(run.style, &run.text)?;
+                runs.push(PlacedRun { run, x, y, width });
+                x += width;
+            }
+        }
+    }
+
+    let height = if line_count == 0 {
+        0.0
+    } else {
+        (line_count - 1) as f32 * line_height + font_size
+    };
+    Ok(TextBlock {
+        runs,
+        metrics: TextMetrics {
+            width: block_width,
+            height,
+            baseline: ascent,
+            line_count,
+        },
+    })
+}
+
+fn trim_trailing_whitespace(runs: &mut Vec<TextRun>) {
+    while let Some(last) = runs.last_mut() {
+        let trimmed = last.text.trim_end().len();
+        last.text.truncate(trimmed);
+        if !last.text.is_empty() {
+            break;
+        }
+        runs.pop();
+    }
+}
+
+// Whitespace tokens between words; leading whitespace is not a gap.
+fn count_gaps(runs: &[TextRun]) -> usize {
+    let mut gaps = 0;
+    let mut seen_word = false;
+    for run in runs {
+        for token in split_tokens(&run.text) {
+            if token.trim().is_empty() {
+                if seen_word {
+                    gaps += 1;
+                }
+            } else {
+                seen_word = true;
+            }
+        }
+    }
+    gaps
+}
+
+// Word-wraps `text` against `max_width` using a backend-provided measure function,
+// so raylib and the software rasterizer break lines identically.
+pub fn layout_lines(
+    text: &TextObject,
+    mut measure: impl FnMut(StyleFlags, &str) -> Result<f32>,
+) -> Result<Vec<LineLayout>> {
+    let max_width = if text.max_width <= 0.0 {
+        f32::INFINITY
+    } else {
+        text.max_width
+    };
+    let mut lines = Vec::new();
+    let mut current = LineLayout {
+        runs: Vec::new(),
+        ends_paragraph: false,
+    };
+    let mut line_width = 0.0;
+
+    for run in &text.text.runs {
+        let parts = split_newlines(&run.text);
+        for (idx, part) in parts.iter().enumerate() {
+            if idx > 0 {
+                current.ends_paragraph = true;
+                lines.push(current);
+                current = LineLayout {
+                    runs: Vec::new(),
+                    ends_paragraph: false,
+                };
+                line_width = 0.0;
+            }
+
+            for token in split_tokens(part) {
+                let token_width = measure(run.style, &token)?;
+
+                if line_width + token_width <= max_width || line_width == 0.0 {
+                    push_run(&mut current.runs, run.style, &token);
+                    line_width += token_width;
+                    continue;
+                }
+
+                if token_width > max_width && line_width == 0.0 {
+                    for ch in token.chars() {
+                        let s = ch.to_string();
+                        let w = measure(run.style, &s)?;
+                        if line_width + w > max_width && line_width > 0.0 {
+                            lines.push(current);
+                            current = LineLayout {
+                                runs: Vec::new(),
+                                ends_paragraph: false,
+                            };
+                            line_width = 0.0;
+                        }
+                        push_run(&mut current.runs, run.style, &s);
+                        line_width += w;
+                    }
+                    continue;
+                }
+
+                lines.push(current);
+                current = LineLayout {
+                    runs: Vec::new(),
+                    ends_paragraph: false,
+                };
+                line_width = 0.0;
+                if token.trim().is_empty() {
+                    continue;
+                }
+                push_run(&mut current.runs, run.style, &token);
+                line_width += token_width;
+            }
+        }
+    }
+
+    current.ends_paragraph = true;
+    lines.push(current);
+    Ok(lines)
+}
+
+fn push_run(runs: &mut Vec<TextRun>, style: StyleFlags, text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    if let Some(last) = runs.last_mut()
+        && last.style == style
+    {
+        last.text.push_str(text);
+        return;
+    }
+    runs.push(TextRun {
+        text: text.to_string(),
+        style,
+    });
+}
+
+fn split_newlines(text: &str) -> Vec<String> {
+    text.split('\n').map(|s| s.to_string()).collect()
+}
+
+fn split_tokens(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut buf = String::new();
+    let mut last_space = None;
+    for ch in text.chars() {
+        let is_space = ch.is_whitespace();
+        match last_space {
+            None => {
+                last_space = Some(is_space);
+                buf.push(ch);
+            }
+            Some(was_space) if was_space == is_space => buf.push(ch),
+            Some(_) => {
+                tokens.push(buf.clone());
+                buf.clear();
+                buf.push(ch);
+                last_space = Some(is_space);
+            }
+        }
+    }
+    if !buf.is_empty() {
+        tokens.push(buf);
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{Color, FontFamily, StyledText};
+
+    // Every character is 10 px wide; lines are 20 + 4 px apart.
+    const CHAR: f32 = 10.0;
+    const SIZE: f32 = 20.0;
+    const ASCENT: f32 = 15.0;
+
+    fn text(markdown: &str, align: TextAlign) -> TextObject {
+        TextObject {
+            text: StyledText::from_markdown(markdown),
+            font: FontFamily::default(),
+            font_size: SIZE,
+            spacing: 0.0,
+            max_width: 0.0,
+            color: Color::WHITE,
+            line_spacing: 4.0,
+            align,
+            vertical_align: VerticalAlign::Top,
+        }
+    }
+
+    fn layout(text: &TextObject) -> TextBlock {
+        layout_block(text, SIZE, ASCENT, |_, token| {
+            Ok(token.chars().count() as f32 * CHAR)
+        })
+        .unwrap()
+    }
+
+    // (text, x, y, width) of every placed run.
+    fn placed(block: &TextBlock) -> Vec<(&str, f32, f32, f32)> {
+        block
+            .runs
+            .iter()
+            .map(|p| (p.run.text.as_str(), p.x, p.y, p.width))
+            .collect()
+    }
+
+    #[test]
+    fn measures_the_block() {
+        let block = layout(&text("one\nthree", TextAlign::Left));
+        assert_eq!(
+            block.metrics,
+            TextMetrics {
+                width: 50.0,
+                height: 24.0 + SIZE,
+                baseline: ASCENT,
+                line_count: 2,
+            }
+        );
+
+        // Trailing spaces do not widen the block.
+        assert_eq!(layout(&text("ab   ", TextAlign::Left)).metrics.width, 20.0);
+        let empty = layout(&text("", TextAlign::Left)).metrics;
+        assert_eq!((empty.width, empty.line_count), (0.0, 1));
+    }
+
+    #[test]
+    fn aligns_lines_within_the_widest_line() {
+        let source = "abcd\nab";
+        let left = layout(&text(source, TextAlign::Left));
+        assert_eq!(placed(&left), [("abcd", 0.0, 0.0, 40.0), ("ab", 0.0, 24.0, 20.0)]);
+
+        let center = layout(&text(source, TextAlign::Center));
+        assert_eq!(placed(&center), [("abcd", 0.0, 0.0, 40.0), ("ab", 10.0, 24.0, 20.0)]);
+
+        let right = layout(&text(source, TextAlign::Right));
+        assert_eq!(placed(&right), [("abcd", 0.0, 0.0, 40.0), ("ab", 20.0, 24.0, 20.0)]);
+    }
+
+    #[test]
+    fn places_styled_runs_side_by_side() {
+        let block = layout(&text("ab **cd**", TextAlign::Right));
+        assert_eq!(placed(&block), [("ab ", 0.0, 0.0, 30.0), ("cd", 30.0, 0.0, 20.0)]);
+        assert!(block.runs[1].run.style.bold);
+    }
+
+    #[test]
+    fn justify_widens_gaps_except_on_the_last_line() {
+        // Wraps to "aa b c" / "dddddddd": the first line has 20 px of slack
+        // over two gaps, and the last line is left as is.
+        let mut object = text("aa b c dddddddd", TextAlign::Justify);
+        object.max_width = 80.0;
+        let block = layout(&object);
+        assert_eq!(
+            placed(&block),
+            [
+                ("aa", 0.0, 0.0, 20.0),
+                (" ", 20.0, 0.0, 20.0),
+                ("b", 40.0, 0.0, 10.0),
+                (" ", 50.0, 0.0, 20.0),
+                ("c", 70.0, 0.0, 10.0),
+                ("dddddddd", 0.0, 24.0, 80.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn justify_keeps_leading_whitespace_width() {
+        // Wraps to "  a b" / "abcdefghi".
+        let mut object = text("  a b abcdefghi", TextAlign::Justify);
+        object.max_width = 90.0;
+        let block = layout(&object);
+        // 40 px of slack goes to the one gap between words, not the indent,
+        // so the line still ends at the block edge.
+        assert_eq!(
+            placed(&block),
+            [
+                ("  ", 0.0, 0.0, 20.0),
+                ("a", 20.0, 0.0, 10.0),
+                (" ", 30.0, 0.0, 50.0),
+                ("b", 80.0, 0.0, 10.0),
+                ("abcdefghi", 0.0, 24.0, 90.0),
+            ]
+        );
+
+        // Lines before an explicit newline end a paragraph and stay as is.
+        let block = layout(&text("a b\nabcdefghi", TextAlign::Justify));
+        assert_eq!(placed(&block)[0], ("a b", 0.0, 0.0, 30.0));
+    }
+
+    #[test]
+    fn default_anchor_follows_the_alignment() {
+        let metrics = TextMetrics {
+            width: 100.0,
+            height: 60.0,
+            baseline: 15.0,
+            line_count: 2,
+        };
+        let anchor = |align, vertical_align| {
+            let mut object = text("", align);
+            object.vertical_align = vertical_align;
+            metrics.default_anchor(&object)
+        };
+        assert_eq!(anchor(TextAlign::Left, VerticalAlign::Top), Anchor::normalized(0.0, 0.0));
+        assert_eq!(anchor(TextAlign::Justify, VerticalAlign::Top), Anchor::normalized(0.0, 0.0));
+        assert_eq!(
+            anchor(TextAlign::Center, VerticalAlign::Middle),
+            Anchor::normalized(0.5, 0.5)
+        );
+        assert_eq!(
+            anchor(TextAlign::Right, VerticalAlign::Bottom),
+            Anchor::normalized(1.0, 1.0)
+        );
+        assert_eq!(
+            anchor(TextAlign::Left, VerticalAlign::Baseline),
+            Anchor::normalized(0.0, 0.25)
+        );
+
+        let empty = TextMetrics {
+            height: 0.0,
+            ..metrics
+        };
+        let mut object = text("", TextAlign::Left);
+        object.vertical_align = VerticalAlign::Baseline;
+        assert_eq!(empty.default_anchor(&object), Anchor::normalized(0.0, 0.0));
+    }
+}
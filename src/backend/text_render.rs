@@ -1,12 +1,12 @@
 use anyhow::Result;
 use raylib::prelude::*;
 
+use crate::backend::anchor::{rotate_screen, text_anchor_offset};
+use crate::backend::raylib_draw::{graph_to_screen, to_raylib_color};
 use crate::backend::resources::{measure_text, FontRef, ResourceCache};
-use crate::scene::{StyleFlags, TextObject, TextRun, Transform, Vec2};
-
-pub struct LineLayout {
-    pub runs: Vec<TextRun>,
-}
+pub use crate::backend::text_layout::LineLayout;
+use crate::backend::text_layout::{layout_block, layout_lines, leading_style, TextMetrics};
+use crate::scene::{StyleFlags, TextObject, Transform, Vec2};
 
 pub fn draw_text_block(
     d: &mut impl RaylibDraw,
@@ -16,161 +16,85 @@ pub fn draw_text_block(
     text: &TextObject,
     transform: &Transform,
 ) -> Result<()> {
-    let origin = graph_to_screen(transform.pos, width, height);
+    let pivot = graph_to_screen(transform.pos, width, height);
     let font_size = text.font_size * transform.scale.y.max(0.0);
     let spacing = text.spacing;
-    let line_height = font_size + text.line_spacing;
-
-    let lines = layout_text(text, cache, font_size, spacing)?;
 
-    let mut y = origin.y;
-    for line in lines {
-        let mut x = origin.x;
-        for run in line.runs {
-            let font = cache.resolve_font(&text.font, run.style)?;
-            let tint = to_raylib_color(text.color, transform.opacity);
-            let position = Vector2::new(x, y);
-            let origin_vec = Vector2::new(0.0, 0.0);
-            draw_text_pro(
-                d,
-                font,
-                &run.text,
-                position,
-                origin_vec,
+    let ascent = cache.ascent(&text.font, leading_style(text), font_size);
+    let block = layout_block(text, font_size, ascent, |style, token| {
+        measure_token(cache, text, style, token, font_size, spacing)
+    })?;
+    let metrics = block.metrics;
+    let anchor = text_anchor_offset(
+        transform,
+        metrics.default_anchor(text),
+        metrics.width,
+        metrics.height,
+    );
+
+    // Every run rotates about the shared pivot so the block turns as one.
+    let tint = to_raylib_color(text.color, transform.opacity);
+    for placed in block.runs {
+        let font = cache.resolve_font(&text.font, placed.run.style)?;
+        let local = Vec2 {
+            x: placed.x - anchor.x,
+            y: placed.y - anchor.y,
+        };
+        draw_text_pro(
+            d,
+            font,
+            &placed.run.text,
+            pivot,
+            Vector2::new(-local.x, -local.y),
+            transform.rotation,
+            font_size,
+            spacing,
+            tint,
+        );
+
+        if placed.run.style.underline {
+            let underline_y = local.y + font_size * 0.9;
+            let start = rotate_screen(Vec2 { x: local.x, y: underline_y }, transform.rotation);
+            let end = rotate_screen(
+                Vec2 {
+                    x: local.x + placed.width,
+                    y: underline_y,
+                },
                 transform.rotation,
-                font_size,
-                spacing,
+            );
+            d.draw_line_ex(
+                Vector2::new(pivot.x + start.x, pivot.y + start.y),
+                Vector2::new(pivot.x + end.x, pivot.y + end.y),
+                2.0,
                 tint,
             );
-
-            let width = measure_text(font, &run.text, font_size, spacing);
-            if run.style.underline {
-                let underline_y = y + font_size * 0.9;
-                d.draw_line_ex(
-                    Vector2::new(x, underline_y),
-                    Vector2::new(x + width, underline_y),
-                    2.0,
-                    tint,
-                );
-            }
-
-            x += width;
         }
-        y += line_height;
     }
 
     Ok(())
 }
 
+pub fn measure_text_block(
+    text: &TextObject,
+    cache: &ResourceCache,
+    font_size: f32,
+) -> Result<TextMetrics> {
+    let ascent = cache.ascent(&text.font, leading_style(text), font_size);
+    let block = layout_block(text, font_size, ascent, |style, token| {
+        measure_token(cache, text, style, token, font_size, text.spacing)
+    })?;
+    Ok(block.metrics)
+}
+
 pub fn layout_text(
     text: &TextObject,
     cache: &ResourceCache,
     font_size: f32,
     spacing: f32,
 ) -> Result<Vec<LineLayout>> {
-    let max_width = if text.max_width <= 0.0 {
-        f32::INFINITY
-    } else {
-        text.max_width
-    };
-    let mut lines = Vec::new();
-    let mut current = LineLayout { runs: Vec::new() };
-    let mut line_width = 0.0;
-
-    for run in &text.text.runs {
-        let parts = split_newlines(&run.text);
-        for (idx, part) in parts.iter().enumerate() {
-            if idx > 0 {
-                lines.push(current);
-                current = LineLayout { runs: Vec::new() };
-                line_width = 0.0;
-            }
-
-            for token in split_tokens(part) {
-                let token_width =
-                    measure_token(cache, &text, run.style, &token, font_size, spacing)?;
-
-                if line_width + token_width <= max_width || line_width == 0.0 {
-                    push_run(&mut current.runs, run.style, &token);
-                    line_width += token_width;
-                    continue;
-                }
-
-                if token_width > max_width && line_width == 0.0 {
-                    for ch in token.chars() {
-                        let s = ch.to_string();
-                        let w = measure_token(cache, &text, run.style, &s, font_size, spacing)?;
-                        if line_width + w > max_width && line_width > 0.0 {
-                            lines.push(current);
-                            current = LineLayout { runs: Vec::new() };
-                            line_width = 0.0;
-                        }
-                        push_run(&mut current.runs, run.style, &s);
-                        line_width += w;
-                    }
-                    continue;
-                }
-
-                lines.push(current);
-                current = LineLayout { runs: Vec::new() };
-                line_width = 0.0;
-                if token.trim().is_empty() {
-                    continue;
-                }
-                push_run(&mut current.runs, run.style, &token);
-                line_width += token_width;
-            }
-        }
-    }
-
-    lines.push(current);
-    Ok(lines)
-}
-
-fn push_run(runs: &mut Vec<TextRun>, style: StyleFlags, text: &str) {
-    if text.is_empty() {
-        return;
-    }
-    if let Some(last) = runs.last_mut() {
-        if last.style == style {
-            last.text.push_str(text);
-            return;
-        }
-    }
-    runs.push(TextRun {
-        text: text.to_string(),
-        style,
-    });
-}
-
-fn split_newlines(text: &str) -> Vec<String> {
-    text.split('\n').map(|s| s.to_string()).collect()
-}
-
-fn split_tokens(text: &str) -> Vec<String> {
-    let mut tokens = Vec::new();
-    let mut buf = String::new();
-    let mut last_space = None;
-    for ch in text.chars() {
-        let is_space = ch.is_whitespace();
-        match last_space {
-            None => {
-                last_space = Some(is_space);
-                buf.push(ch);
-            }
-            Some(was_space) if was_space == is_space => buf.push(ch),
-            Some(_) => {
-                tokens.push(buf.clone());
-                buf.clear();
-                buf.push(ch);
-                last_space = Some(is_space);
-            }
-        }
-    }
-    if !buf.is_empty() {
-        tokens.push(buf);
-    }
-    tokens
+    layout_lines(text, |style, token| {
+        measure_token(cache, text, style, token, font_size, spacing)
+    })
 }
 
 fn measure_token(
@@ -203,14 +127,3 @@ fn draw_text_pro(
         FontRef::Loaded(font) => d.draw_text_pro(font, text, position, origin, rotation, font_size, spacing, tint),
     }
 }
-
-fn graph_to_screen(pos: Vec2, width: u32, height: u32) -> Vector2 {
-    Vector2::new(width as f32 / 2.0 + pos.x, height as f32 / 2.0 - pos.y)
-}
-
-fn to_raylib_color(color: crate::scene::Color, opacity: f32) -> raylib::prelude::Color {
-    let alpha = (color.a as f32 * opacity.clamp(0.0, 1.0))
-        .round()
-        .clamp(0.0, 255.0) as u8;
-    raylib::prelude::Color::new(color.r, color.g, color.b, alpha)
-}
@@ -0,0 +1,478 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+
+use script_2_script::audio::{
+    measure_loudness, mix_timeline, write_wav, LoudnessTarget, DEFAULT_SAMPLE_RATE,
+};
+use script_2_script::encoder::{
+    ffprobe_metadata, supported_codecs, AnimatedFormat, AnimatedImageConfig, AnimatedImageEncoder,
+    Dither, SequenceFormat,
+};
+use script_2_script::{
+    mux_video_audio, trim_audio, Color, ContactSheet, EncoderConfig, FfmpegVideoEncoder,
+    PipelineOptions, PreviewAudio, Project, RaylibPreview, RaylibRender, RenderProgress, Renderer,
+    SoftwareRender, VideoCodec,
+};
+
+const USAGE: &str = "\
+usage: s2s <command> <file> [options]
+
+commands:
+  preview <project>   open a preview window with audio
+                      (space: pause, left/right: seek 5s, r: restart)
+  render <project>    render video + audio to an mp4 (or .webm/.mov, see --preset),
+                      or a silent animation when --output ends in .gif/.apng
+  frame <project>     write a single frame to a png (requires --time)
+  sheet <project>     tile evenly spaced frames into one contact-sheet png
+  sequence <project>  write every frame as numbered png/exr files
+  probe <video>       print width, height and fps of a video file
+  encoders            list the video codecs the local ffmpeg can encode
+
+options:
+  --start_time <s>    start of the range to preview/render (default 0)
+  --end_time <s>      end of the range to preview/render (default: duration)
+  --time <s>          frame time for `frame`
+  --output <path>     output file, or directory for `sequence`
+                      (default: output/<project>.mp4, .png, .sheet.png or .frames/)
+  --width <px>        output width (default 800)
+  --height <px>       output height (default 600)
+  --software          render on the CPU instead of raylib (no window/GPU)
+  --keep-temp         keep intermediate video/audio files
+  --progress          print render progress
+  --ducking-curve <path>  write the music ducking gain curve as CSV (render)
+  --lufs <target>     normalize the mix to an integrated loudness, e.g. -14 (render)
+  --preset <name>     encoder preset: draft, web, master, alpha or alpha-webm
+                      (render, default: x264 crf 18); --output must use
+                      a container the preset's codec fits in (.mp4, .mov,
+                      .mkv or .webm)
+  --transparent       render on a transparent background; `render` defaults to
+                      the alpha (ProRes 4444 .mov) preset
+  --gif-fps <n>       frame rate of .gif/.apng output (default: timeline fps)
+  --gif-width <px>    scale .gif/.apng output to this width
+  --loop <n>          .gif/.apng plays this many times (default 0: forever)
+  --dither <name>     gif dither: none, bayer, floyd_steinberg, sierra2 (default)
+                      or sierra2_4a
+  --frames <n>        contact sheet frame count (default 12)
+  --columns <n>       contact sheet columns (default 4)
+  --thumb-width <px>  contact sheet thumbnail width (default 320)
+  --format <png|exr>  `sequence` image format (default png)
+  --threads <n>       timeline sampling threads for render (default: cores - 2)";
+
+const BACKGROUND: Color = Color {
+    r: 16,
+    g: 16,
+    b: 20,
+    a: 255,
+};
+
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1);
+    let command = match args.next() {
+        Some(command) if command != "--help" && command != "-h" => command,
+        _ => {
+            println!("{USAGE}");
+            return Ok(());
+        }
+    };
+    if command == "encoders" {
+        return encoders();
+    }
+    let opts = Options::parse(args)?;
+
+    match command.as_str() {
+        "preview" => preview(&opts),
+        "render" => render(&opts),
+        "frame" => frame(&opts),
+        "sheet" => sheet(&opts),
+        "sequence" => sequence(&opts),
+        "probe" => probe(&opts),
+        other => bail!("unknown command: {other}\n\n{USAGE}"),
+    }
+}
+
+fn preview(opts: &Options) -> Result<()> {
+    let project = Project::load(&opts.input)?;
+    let (start_time, end_time) = opts.range(project.timeline.duration)?;
+    let preview = RaylibPreview::new(opts.width, opts.height, BACKGROUND);
+    if project.music.is_empty() && project.dialogue.is_empty() && project.sfx.is_empty() {
+        return preview.run_range(&project.timeline, start_time, end_time);
+    }
+    let mut audio = PreviewAudio::new(&project.music, &project.dialogue, &project.sfx)?;
+    preview.run_with_audio(&project.timeline, start_time, end_time, &mut audio)
+}
+
+fn render(opts: &Options) -> Result<()> {
+    let project = Project::load(&opts.input)?;
+    let timeline = &project.timeline;
+    let (start_time, end_time) = opts.range(timeline.duration)?;
+    // Renders are not composited over the base video yet; refuse rather than
+    // produce a video that silently leaves it out.
+    if !project.base_video.is_empty() {
+        bail!(
+            "render does not support base_video yet; stitch it with build_base_video \
+             and render the project without it"
+        );
+    }
+
+    if let Some(format) = opts.output.as_deref().and_then(AnimatedFormat::from_path) {
+        return render_animation(opts, &project, format, start_time, end_time);
+    }
+
+    let config = match (&opts.preset, opts.transparent) {
+        (Some(name), _) => EncoderConfig::preset(name)?,
+        (None, true) => EncoderConfig::alpha(),
+        (None, false) => EncoderConfig::default(),
+    };
+    if opts.transparent && !config.has_alpha() {
+        bail!("--transparent needs a preset with an alpha channel (alpha or alpha-webm)");
+    }
+    let containers = config.codec.containers();
+    let output_path = opts.output_or(config.codec.container());
+    let extension = output_path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+    let Some(container) = extension.filter(|ext| containers.contains(&ext.as_str())) else {
+        bail!(
+            "{:?} video needs a .{} output, got {}",
+            config.codec,
+            containers.join(", ."),
+            output_path.display()
+        );
+    };
+    std::fs::create_dir_all(output_path.parent().unwrap_or(Path::new(".")))?;
+    let temp_video = sibling_path(&output_path, "video", &container);
+    let audio_full = sibling_path(&output_path, "audio_full", "wav");
+    let audio_clip = sibling_path(&output_path, "audio_clip", "wav");
+
+    let mut renderer = opts.renderer()?;
+    let mut encoder =
+        FfmpegVideoEncoder::start_with(opts.width, opts.height, timeline.fps, &config, &temp_video)?;
+    let stats = renderer.render_timeline_pipelined(
+        timeline,
+        start_time,
+        end_time,
+        &opts.pipeline(),
+        &mut encoder,
+    )?;
+    encoder.finish()?;
+    println!("{stats}");
+
+    // Audio: mix the full timeline, trim to the rendered range, then mux.
+    if !project.music.is_empty() || !project.dialogue.is_empty() || !project.sfx.is_empty() {
+        let mut options = project.mix_options();
+        if let Some(lufs) = opts.lufs {
+            let ceiling = options.loudness.map_or(-1.0, |target| target.true_peak_dbtp);
+            let target = LoudnessTarget::new(lufs, ceiling);
+            target.validate().context("--lufs")?;
+            options.loudness = Some(target);
+        }
+        let mix = mix_timeline(
+            &project.music,
+            &project.dialogue,
+            &project.sfx,
+            &options,
+            timeline.duration,
+            DEFAULT_SAMPLE_RATE,
+        )?;
+        write_wav(&mix.audio, &audio_full)?;
+        if let (Some(path), Some(curve)) = (&opts.ducking_curve, &mix.ducking) {
+            curve.write_csv(path)?;
+            println!("ducking curve: {}", path.display());
+        }
+        match &mix.loudness {
+            Some(report) => println!("{report}"),
+            None => println!("audio: {}", measure_loudness(&mix.audio)),
+        }
+        let audio_for_mux = if start_time == 0.0 && end_time == timeline.duration {
+            &audio_full
+        } else {
+            trim_audio(&audio_full, start_time, end_time, &audio_clip)?;
+            &audio_clip
+        };
+        mux_video_audio(&temp_video, audio_for_mux, &output_path)?;
+    } else {
+        std::fs::copy(&temp_video, &output_path)
+            .with_context(|| format!("failed to copy video to {}", output_path.display()))?;
+    }
+
+    if !opts.keep_temp {
+        let _ = std::fs::remove_file(&temp_video);
+        let _ = std::fs::remove_file(&audio_full);
+        let _ = std::fs::remove_file(&audio_clip);
+    }
+
+    println!("rendered: {}", output_path.display());
+    Ok(())
+}
+
+// GIF/APNG output: video only, no audio to mux.
+fn render_animation(
+    opts: &Options,
+    project: &Project,
+    format: AnimatedFormat,
+    start_time: f32,
+    end_time: f32,
+) -> Result<()> {
+    let timeline = &project.timeline;
+    let output_path = opts.output_or(format.extension());
+    std::fs::create_dir_all(output_path.parent().unwrap_or(Path::new(".")))?;
+    let config = AnimatedImageConfig {
+        fps: opts.gif_fps,
+        width: opts.gif_width,
+        loop_count: opts.loop_count,
+        dither: opts.dither,
+        ..AnimatedImageConfig::new(format)
+    };
+
+    let mut renderer = opts.renderer()?;
+    let mut encoder =
+        AnimatedImageEncoder::start(opts.width, opts.height, timeline.fps, &config, &output_path)?;
+    let stats = renderer.render_timeline_pipelined(
+        timeline,
+        start_time,
+        end_time,
+        &opts.pipeline(),
+        &mut encoder,
+    )?;
+    encoder.finish()?;
+    println!("{stats}");
+
+    println!("rendered: {}", output_path.display());
+    Ok(())
+}
+
+fn frame(opts: &Options) -> Result<()> {
+    let project = Project::load(&opts.input)?;
+    let time = opts.time.context("frame requires --time <seconds>")?;
+
+    let output_path = opts.output_or("png");
+    std::fs::create_dir_all(output_path.parent().unwrap_or(Path::new(".")))?;
+    let mut renderer = opts.renderer()?;
+    renderer.render_frame_png(&project.timeline, time, &output_path)?;
+
+    println!("frame {time:.3}s: {}", output_path.display());
+    Ok(())
+}
+
+fn sheet(opts: &Options) -> Result<()> {
+    let project = Project::load(&opts.input)?;
+    let sheet = ContactSheet {
+        thumb_width: opts.thumb_width,
+        ..ContactSheet::new(opts.frames, opts.columns)
+    };
+
+    let output_path = opts.output_or("sheet.png");
+    std::fs::create_dir_all(output_path.parent().unwrap_or(Path::new(".")))?;
+    let mut renderer = opts.renderer()?;
+    renderer.render_contact_sheet(&project.timeline, &sheet, &output_path)?;
+
+    println!("contact sheet ({} frames): {}", sheet.frames, output_path.display());
+    Ok(())
+}
+
+fn sequence(opts: &Options) -> Result<()> {
+    let project = Project::load(&opts.input)?;
+    let (start_time, end_time) = opts.range(project.timeline.duration)?;
+
+    let output_dir = opts.output_or("frames");
+    let mut renderer = opts.renderer()?;
+    let frames = renderer.render_image_sequence(
+        &project.timeline,
+        start_time,
+        end_time,
+        &output_dir,
+        opts.sequence_format,
+    )?;
+
+    println!("{frames} frames: {}", output_dir.display());
+    Ok(())
+}
+
+fn probe(opts: &Options) -> Result<()> {
+    let meta = ffprobe_metadata(&opts.input)?;
+    println!("width={}", meta.width);
+    println!("height={}", meta.height);
+    println!("fps={:.3}", meta.fps);
+    Ok(())
+}
+
+fn encoders() -> Result<()> {
+    let supported = supported_codecs()?;
+    for codec in VideoCodec::ALL {
+        let status = if supported.contains(&codec) { "yes" } else { "no" };
+        println!("{:<8} {:<12} {status}", format!("{codec:?}"), codec.encoder());
+    }
+    Ok(())
+}
+
+struct Options {
+    input: PathBuf,
+    start_time: Option<f32>,
+    end_time: Option<f32>,
+    time: Option<f32>,
+    output: Option<PathBuf>,
+    width: u32,
+    height: u32,
+    software: bool,
+    keep_temp: bool,
+    progress: bool,
+    ducking_curve: Option<PathBuf>,
+    lufs: Option<f32>,
+    preset: Option<String>,
+    transparent: bool,
+    gif_fps: Option<u32>,
+    gif_width: Option<u32>,
+    loop_count: u32,
+    dither: Dither,
+    frames: u32,
+    columns: u32,
+    thumb_width: u32,
+    sequence_format: SequenceFormat,
+    threads: Option<usize>,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut input = None;
+        let mut opts = Options {
+            input: PathBuf::new(),
+            start_time: None,
+            end_time: None,
+            time: None,
+            output: None,
+            width: 800,
+            height: 600,
+            software: false,
+            keep_temp: false,
+            progress: false,
+            ducking_curve: None,
+            lufs: None,
+            preset: None,
+            transparent: false,
+            gif_fps: None,
+            gif_width: None,
+            loop_count: 0,
+            dither: Dither::default(),
+            frames: 12,
+            columns: 4,
+            thumb_width: 320,
+            sequence_format: SequenceFormat::default(),
+            threads: None,
+        };
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .with_context(|| format!("{name} requires a value"))
+            };
+            match arg.as_str() {
+                "--start_time" => opts.start_time = Some(value("--start_time")?.parse()?),
+                "--end_time" => opts.end_time = Some(value("--end_time")?.parse()?),
+                "--time" => opts.time = Some(value("--time")?.parse()?),
+                "--output" => opts.output = Some(PathBuf::from(value("--output")?)),
+                "--width" => opts.width = value("--width")?.parse()?,
+                "--height" => opts.height = value("--height")?.parse()?,
+                "--software" => opts.software = true,
+                "--keep-temp" => opts.keep_temp = true,
+                "--progress" => opts.progress = true,
+                "--transparent" => opts.transparent = true,
+                "--gif-fps" => opts.gif_fps = Some(value("--gif-fps")?.parse()?),
+                "--gif-width" => opts.gif_width = Some(value("--gif-width")?.parse()?),
+                "--loop" => opts.loop_count = value("--loop")?.parse()?,
+                "--dither" => opts.dither = parse_dither(&value("--dither")?)?,
+                "--frames" => opts.frames = value("--frames")?.parse()?,
+                "--columns" => opts.columns = value("--columns")?.parse()?,
+                "--threads" => opts.threads = Some(value("--threads")?.parse()?),
+                "--thumb-width" => opts.thumb_width = value("--thumb-width")?.parse()?,
+                "--format" => {
+                    opts.sequence_format = match value("--format")?.as_str() {
+                        "png" => SequenceFormat::Png,
+                        "exr" => SequenceFormat::Exr,
+                        other => bail!("unknown sequence format: {other}"),
+                    }
+                }
+                "--lufs" => opts.lufs = Some(value("--lufs")?.parse()?),
+                "--preset" => opts.preset = Some(value("--preset")?),
+                "--ducking-curve" => {
+                    opts.ducking_curve = Some(PathBuf::from(value("--ducking-curve")?))
+                }
+                other if other.starts_with("--") => bail!("unknown argument: {other}"),
+                other => {
+                    if input.is_some() {
+                        bail!("unexpected argument: {other}");
+                    }
+                    input = Some(PathBuf::from(other));
+                }
+            }
+        }
+
+        opts.input = input.context("missing input file")?;
+        if opts.width == 0 || opts.height == 0 {
+            bail!("--width and --height must be > 0");
+        }
+        Ok(opts)
+    }
+
+    fn range(&self, duration: f32) -> Result<(f32, f32)> {
+        let start_time = self.start_time.unwrap_or(0.0);
+        let end_time = self.end_time.unwrap_or(duration);
+        if start_time < 0.0 || end_time <= start_time || end_time > duration {
+            bail!("start/end time must satisfy 0 <= start < end <= duration");
+        }
+        Ok((start_time, end_time))
+    }
+
+    fn output_or(&self, ext: &str) -> PathBuf {
+        if let Some(path) = &self.output {
+            return path.clone();
+        }
+        let stem = self
+            .input
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "render".to_string());
+        PathBuf::from(format!("output/{stem}.{ext}"))
+    }
+
+    fn pipeline(&self) -> PipelineOptions {
+        let defaults = PipelineOptions::default();
+        PipelineOptions {
+            sample_workers: self.threads.unwrap_or(defaults.sample_workers),
+            progress: RenderProgress {
+                enabled: self.progress,
+                ..RenderProgress::default()
+            },
+            ..defaults
+        }
+    }
+
+    fn renderer(&self) -> Result<Box<dyn Renderer>> {
+        let bg = if self.transparent { Color::TRANSPARENT } else { BACKGROUND };
+        Ok(if self.software {
+            Box::new(SoftwareRender::new(self.width, self.height, bg)?)
+        } else {
+            Box::new(RaylibRender::new(self.width, self.height, bg)?)
+        })
+    }
+}
+
+fn parse_dither(name: &str) -> Result<Dither> {
+    Ok(match name {
+        "none" => Dither::None,
+        "bayer" => Dither::Bayer { scale: 2 },
+        "floyd_steinberg" => Dither::FloydSteinberg,
+        "sierra2" => Dither::Sierra2,
+        "sierra2_4a" => Dither::Sierra2_4a,
+        other => bail!("unknown dither: {other}"),
+    })
+}
+
+fn sibling_path(output_path: &Path, name: &str, ext: &str) -> PathBuf {
+    let stem = output_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "render".to_string());
+    output_path.with_file_name(format!("{stem}.{name}.{ext}"))
+}
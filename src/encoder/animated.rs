@@ -0,0 +1,432 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+// Animated GIF or APNG output from the same RGBA frame stream the video
+// encoder takes. Frames are spooled to a temp file next to the output so the
+// GIF palette can be built from the whole clip before any frame is mapped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimatedImageConfig {
+    pub format: AnimatedFormat,
+    // Output frame rate; frames are dropped to reach it. Defaults to the
+    // render fps.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fps: Option<u32>,
+    // Output width in pixels, height following the aspect ratio.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    // How many times the animation plays; 0 loops forever.
+    #[serde(default)]
+    pub loop_count: u32,
+    // GIF only: palette size, 2..=256.
+    #[serde(default = "default_max_colors")]
+    pub max_colors: u32,
+    #[serde(default)]
+    pub palette: PaletteMode,
+    #[serde(default)]
+    pub dither: Dither,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnimatedFormat {
+    Gif,
+    Apng,
+}
+
+// Which pixels the GIF palette is built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PaletteMode {
+    // One palette from every pixel of every frame.
+    #[default]
+    Full,
+    // One palette favoring what moves; static backgrounds get fewer colors.
+    Diff,
+    // A fresh palette per frame. Best color, largest file.
+    PerFrame,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Dither {
+    None,
+    // Ordered dither; `scale` 0..=5, higher is less visible pattern.
+    Bayer { scale: u8 },
+    FloydSteinberg,
+    #[default]
+    Sierra2,
+    Sierra2_4a,
+}
+
+fn default_max_colors() -> u32 {
+    256
+}
+
+impl AnimatedFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            AnimatedFormat::Gif => "gif",
+            AnimatedFormat::Apng => "apng",
+        }
+    }
+
+    // `.gif` or `.apng` outputs.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_string_lossy().to_ascii_lowercase();
+        match ext.as_str() {
+            "gif" => Some(AnimatedFormat::Gif),
+            "apng" => Some(AnimatedFormat::Apng),
+            _ => None,
+        }
+    }
+}
+
+impl Dither {
+    fn filter_args(self) -> String {
+        match self {
+            Dither::None => "dither=none".to_string(),
+            Dither::Bayer { scale } => format!("dither=bayer:bayer_scale={scale}"),
+            Dither::FloydSteinberg => "dither=floyd_steinberg".to_string(),
+            Dither::Sierra2 => "dither=sierra2".to_string(),
+            Dither::Sierra2_4a => "dither=sierra2_4a".to_string(),
+        }
+    }
+}
+
+impl AnimatedImageConfig {
+    pub fn new(format: AnimatedFormat) -> Self {
+        Self {
+            format,
+            fps: None,
+            width: None,
+            loop_count: 0,
+            max_colors: default_max_colors(),
+            palette: PaletteMode::default(),
+            dither: Dither::default(),
+        }
+    }
+
+    pub fn gif() -> Self {
+        Self::new(AnimatedFormat::Gif)
+    }
+
+    pub fn apng() -> Self {
+        Self::new(AnimatedFormat::Apng)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.fps == Some(0) || self.width == Some(0) {
+            bail!("animated image fps and width must be > 0");
+        }
+        if !(2..=256).contains(&self.max_colors) {
+            bail!("gif max_colors must be in 2..=256");
+        }
+        if let Dither::Bayer { scale } = self.dither
+            && scale > 5
+        {
+            bail!("bayer dither scale must be in 0..=5");
+        }
+        Ok(())
+    }
+
+    // Frames arrive bottom-up, so every chain starts with `vflip`.
+    fn base_filter(&self) -> String {
+        match self.width {
+            Some(width) => format!("vflip,scale={width}:-1:flags=lanczos"),
+            None => "vflip".to_string(),
+        }
+    }
+
+    fn palettegen_filter(&self) -> String {
+        let stats = match self.palette {
+            PaletteMode::Full => "full",
+            PaletteMode::Diff => "diff",
+            PaletteMode::PerFrame => "single",
+        };
+        format!(
+            "{},palettegen=max_colors={}:stats_mode={stats}",
+            self.base_filter(),
+            self.max_colors
+        )
+    }
+
+    // Maps frames onto the palette from the first pass, which arrives as the
+    // second input. Per-frame palettes can't be stored in one image, so that
+    // mode builds them in the same graph instead.
+    fn paletteuse_filter(&self) -> String {
+        let dither = self.dither.filter_args();
+        match self.palette {
+            PaletteMode::PerFrame => format!(
+                "{},split[frames][stats];[stats]palettegen=max_colors={}:stats_mode=single[palette];\
+                 [frames][palette]paletteuse={dither}:new=1",
+                self.base_filter(),
+                self.max_colors
+            ),
+            PaletteMode::Diff => format!(
+                "[0:v]{}[frames];[frames][1:v]paletteuse={dither}:diff_mode=rectangle",
+                self.base_filter()
+            ),
+            PaletteMode::Full => {
+                format!("[0:v]{}[frames];[frames][1:v]paletteuse={dither}", self.base_filter())
+            }
+        }
+    }
+
+    // Muxer options for the loop count.
+    fn loop_args(&self) -> [String; 2] {
+        match self.format {
+            // GIF counts repeats after the first play, with -1 for none.
+            AnimatedFormat::Gif => {
+                let repeats = match self.loop_count {
+                    0 => 0,
+                    1 => -1,
+                    plays => plays as i64 - 1,
+                };
+                ["-loop".to_string(), repeats.to_string()]
+            }
+            AnimatedFormat::Apng => ["-plays".to_string(), self.loop_count.to_string()],
+        }
+    }
+}
+
+pub struct AnimatedImageEncoder {
+    spool: Option<BufWriter<File>>,
+    spool_path: PathBuf,
+    palette_path: PathBuf,
+    output_path: PathBuf,
+    config: AnimatedImageConfig,
+    width: u32,
+    height: u32,
+    fps: u32,
+    frames_in: u64,
+    frames_out: u64,
+}
+
+impl AnimatedImageEncoder {
+    pub fn start(
+        width: u32,
+        height: u32,
+        fps: u32,
+        config: &AnimatedImageConfig,
+        output_path: &Path,
+    ) -> Result<Self> {
+        config.validate()?;
+        if fps == 0 {
+            bail!("fps must be > 0");
+        }
+        let name = output_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "animation".to_string());
+        let spool_path = output_path.with_file_name(format!("{name}.frames.rgba"));
+        let palette_path = output_path.with_file_name(format!("{name}.palette.png"));
+        let file = File::create(&spool_path)
+            .with_context(|| format!("failed to create {}", spool_path.display()))?;
+
+        Ok(Self {
+            spool: Some(BufWriter::new(file)),
+            spool_path,
+            palette_path,
+            output_path: output_path.to_path_buf(),
+            config: config.clone(),
+            width,
+            height,
+            fps,
+            frames_in: 0,
+            frames_out: 0,
+        })
+    }
+
+    // The rate frames are written at: the configured fps, capped at the input.
+    pub fn output_fps(&self) -> u32 {
+        self.config.fps.map_or(self.fps, |fps| fps.min(self.fps))
+    }
+
+    pub fn write_frame(&mut self, frame: &[u8]) -> Result<()> {
+        let expected = (self.width * self.height * 4) as usize;
+        if frame.len() != expected {
+            bail!(
+                "frame size mismatch: got {}, expected {}",
+                frame.len(),
+                expected
+            );
+        }
+        let keep = keeps_frame(self.frames_in, self.fps, self.output_fps());
+        self.frames_in += 1;
+        if !keep {
+            return Ok(());
+        }
+        let spool = self.spool.as_mut().context("animated image already finished")?;
+        spool.write_all(frame).context("failed to spool frame")?;
+        self.frames_out += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<()> {
+        if let Some(mut spool) = self.spool.take() {
+            spool.flush().context("failed to flush frame spool")?;
+        }
+        let result = if self.frames_out == 0 {
+            Err(anyhow!("no frames written"))
+        } else {
+            match self.config.format {
+                AnimatedFormat::Gif => self.encode_gif(),
+                AnimatedFormat::Apng => self.encode_apng(),
+            }
+        };
+        let _ = std::fs::remove_file(&self.spool_path);
+        let _ = std::fs::remove_file(&self.palette_path);
+        result
+    }
+
+    fn encode_gif(&self) -> Result<()> {
+        let mut paletteuse = self.ffmpeg();
+        if self.config.palette != PaletteMode::PerFrame {
+            // Pass 1: build the palette from the spooled frames.
+            let mut palettegen = self.ffmpeg();
+            palettegen
+                .arg("-vf")
+                .arg(self.config.palettegen_filter())
+                .arg("-update")
+                .arg("1")
+                .arg(&self.palette_path);
+            run(palettegen, "gif palette pass")?;
+            paletteuse.arg("-i").arg(&self.palette_path);
+        }
+
+        // Pass 2: map every frame onto it.
+        paletteuse
+            .arg("-lavfi")
+            .arg(self.config.paletteuse_filter())
+            .args(self.config.loop_args())
+            .arg("-f")
+            .arg("gif")
+            .arg(&self.output_path);
+        run(paletteuse, "gif encode")
+    }
+
+    fn encode_apng(&self) -> Result<()> {
+        let mut cmd = self.ffmpeg();
+        cmd.arg("-vf")
+            .arg(self.config.base_filter())
+            .arg("-c:v")
+            .arg("apng")
+            .arg("-pix_fmt")
+            .arg("rgba")
+            .args(self.config.loop_args())
+            .arg("-f")
+            .arg("apng")
+            .arg(&self.output_path);
+        run(cmd, "apng encode")
+    }
+
+    // ffmpeg reading the spooled frames as its first input.
+    fn ffmpeg(&self) -> Command {
+        let mut cmd = Command::new("ffmpeg");
+        cmd.arg("-y")
+            .arg("-loglevel")
+            .arg("error")
+            .arg("-f")
+            .arg("rawvideo")
+            .arg("-pix_fmt")
+            .arg("rgba")
+            .arg("-s")
+            .arg(format!("{}x{}", self.width, self.height))
+            .arg("-framerate")
+            .arg(self.output_fps().to_string())
+            .arg("-i")
+            .arg(&self.spool_path);
+        cmd
+    }
+}
+
+// Frame decimation: keeps input frame `index` when it starts a new output frame.
+fn keeps_frame(index: u64, in_fps: u32, out_fps: u32) -> bool {
+    let slot = |i: u64| i * out_fps as u64 / in_fps as u64;
+    index == 0 || slot(index) != slot(index - 1)
+}
+
+// Collects stderr while waiting, so a chatty ffmpeg cannot fill the pipe and
+// block before it exits.
+fn run(mut cmd: Command, what: &str) -> Result<()> {
+    let output = cmd
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .context("failed to run ffmpeg (is it on PATH?)")?;
+    if !output.status.success() {
+        bail!(
+            "ffmpeg {what} failed with status {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimates_to_the_output_rate() {
+        let kept = |in_fps, out_fps| {
+            (0..in_fps as u64 * 2)
+                .filter(|&i| keeps_frame(i, in_fps, out_fps))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(kept(30, 10)[..4], [0, 3, 6, 9]);
+        assert_eq!(kept(30, 10).len(), 20);
+        assert_eq!(kept(30, 12).len(), 24);
+        assert_eq!(kept(24, 24).len(), 48);
+    }
+
+    #[test]
+    fn builds_palette_filters_and_loop_args() {
+        let config = AnimatedImageConfig {
+            width: Some(480),
+            max_colors: 64,
+            palette: PaletteMode::Diff,
+            dither: Dither::Bayer { scale: 3 },
+            loop_count: 1,
+            ..AnimatedImageConfig::gif()
+        };
+        config.validate().unwrap();
+        assert_eq!(
+            config.palettegen_filter(),
+            "vflip,scale=480:-1:flags=lanczos,palettegen=max_colors=64:stats_mode=diff"
+        );
+        assert_eq!(
+            config.paletteuse_filter(),
+            "[0:v]vflip,scale=480:-1:flags=lanczos[frames];\
+             [frames][1:v]paletteuse=dither=bayer:bayer_scale=3:diff_mode=rectangle"
+        );
+        assert_eq!(config.loop_args(), ["-loop", "-1"]);
+
+        let per_frame = AnimatedImageConfig {
+            palette: PaletteMode::PerFrame,
+            ..AnimatedImageConfig::gif()
+        };
+        assert_eq!(
+            per_frame.paletteuse_filter(),
+            "vflip,split[frames][stats];[stats]palettegen=max_colors=256:stats_mode=single[palette];\
+             [frames][palette]paletteuse=dither=sierra2:new=1"
+        );
+
+        let forever = AnimatedImageConfig::gif();
+        assert_eq!(forever.loop_args(), ["-loop", "0"]);
+        let thrice = AnimatedImageConfig {
+            loop_count: 3,
+            ..AnimatedImageConfig::apng()
+        };
+        assert_eq!(thrice.loop_args(), ["-plays", "3"]);
+
+        let bad = AnimatedImageConfig {
+            max_colors: 300,
+            ..AnimatedImageConfig::gif()
+        };
+        assert!(bad.validate().is_err());
+    }
+}
@@ -0,0 +1,449 @@
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+// How `FfmpegVideoEncoder` turns raw RGBA frames into a file. `Default` is
+// the original behavior: x264 at CRF 18, yuv420p.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EncoderConfig {
+    pub codec: VideoCodec,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate: Option<RateControl>,
+    // Encoder speed/quality preset, e.g. "slow" for x264/x265, "good" for VP9
+    // (passed as `-deadline`) or "6" for SVT-AV1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
+    // Codec profile, e.g. "high" for x264 or "hq"/"4444" for ProRes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    // Defaults to `VideoCodec::default_pixel_format`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pixel_format: Option<String>,
+    // Keyframe interval in frames.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gop: Option<u32>,
+    // Keyframe interval in seconds, converted to frames at the encode fps.
+    // `gop` wins when both are set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gop_seconds: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<ColorTags>,
+    // Appended to the output options as-is, e.g. `["-movflags", "+faststart"]`.
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VideoCodec {
+    H264,
+    H265,
+    Vp9,
+    Av1,
+    ProRes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RateControl {
+    // Constant quality; lower is better. The scale depends on the codec.
+    Crf(u32),
+    // Average bitrate in kbit/s.
+    Bitrate(u32),
+}
+
+// Color metadata written to the stream. `matrix` also sets the RGB to YUV
+// conversion, so players decode the colors that were rendered.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColorTags {
+    pub primaries: String,
+    pub transfer: String,
+    pub matrix: String,
+    #[serde(default)]
+    pub full_range: bool,
+}
+
+impl VideoCodec {
+    pub const ALL: [VideoCodec; 5] = [
+        VideoCodec::H264,
+        VideoCodec::H265,
+        VideoCodec::Vp9,
+        VideoCodec::Av1,
+        VideoCodec::ProRes,
+    ];
+
+    // The ffmpeg encoder passed to `-c:v`.
+    pub fn encoder(self) -> &'static str {
+        match self {
+            VideoCodec::H264 => "libx264",
+            VideoCodec::H265 => "libx265",
+            VideoCodec::Vp9 => "libvpx-vp9",
+            VideoCodec::Av1 => "libsvtav1",
+            VideoCodec::ProRes => "prores_ks",
+        }
+    }
+
+    pub fn default_pixel_format(self) -> &'static str {
+        match self {
+            VideoCodec::ProRes => "yuv422p10le",
+            _ => "yuv420p",
+        }
+    }
+
+    // File extension of a container that can hold the codec.
+    pub fn container(self) -> &'static str {
+        self.containers()[0]
+    }
+
+    // Extensions of every container that can hold the codec, default first.
+    pub fn containers(self) -> &'static [&'static str] {
+        match self {
+            VideoCodec::H264 | VideoCodec::H265 => &["mp4", "mov", "mkv"],
+            VideoCodec::Av1 => &["mp4", "webm", "mkv"],
+            VideoCodec::Vp9 => &["webm", "mkv"],
+            VideoCodec::ProRes => &["mov"],
+        }
+    }
+}
+
+impl ColorTags {
+    pub fn bt709() -> Self {
+        Self {
+            primaries: "bt709".to_string(),
+            transfer: "bt709".to_string(),
+            matrix: "bt709".to_string(),
+            full_range: false,
+        }
+    }
+
+    // The `scale` filter's name for the matrix, if it has one.
+    fn scale_matrix(&self) -> Option<&'static str> {
+        match self.matrix.as_str() {
+            "bt709" => Some("bt709"),
+            "bt470bg" | "smpte170m" => Some("bt601"),
+            "smpte240m" => Some("smpte240m"),
+            "fcc" => Some("fcc"),
+            "bt2020nc" | "bt2020c" => Some("bt2020"),
+            _ => None,
+        }
+    }
+}
+
+impl Default for EncoderConfig {
+    fn default() -> Self {
+        Self::new(VideoCodec::H264, Some(RateControl::Crf(18)))
+    }
+}
+
+impl EncoderConfig {
+    pub const PRESETS: [&'static str; 5] = ["draft", "web", "master", "alpha", "alpha-webm"];
+
+    pub fn new(codec: VideoCodec, rate: Option<RateControl>) -> Self {
+        Self {
+            codec,
+            rate,
+            preset: None,
+            profile: None,
+            pixel_format: None,
+            gop: None,
+            gop_seconds: None,
+            color: None,
+            extra_args: Vec::new(),
+        }
+    }
+
+    // Fast, small x264 for checking timing and layout.
+    pub fn draft() -> Self {
+        Self {
+            preset: Some("ultrafast".to_string()),
+            ..Self::new(VideoCodec::H264, Some(RateControl::Crf(28)))
+        }
+    }
+
+    // x264 High profile, BT.709, a keyframe every 2 s and the index at the
+    // front so playback starts before the download finishes.
+    pub fn web() -> Self {
+        Self {
+            preset: Some("slow".to_string()),
+            profile: Some("high".to_string()),
+            gop_seconds: Some(2.0),
+            color: Some(ColorTags::bt709()),
+            extra_args: vec!["-movflags".to_string(), "+faststart".to_string()],
+            ..Self::new(VideoCodec::H264, Some(RateControl::Crf(20)))
+        }
+    }
+
+    // ProRes 422 HQ in a .mov, for editing and archiving.
+    pub fn master() -> Self {
+        Self {
+            profile: Some("hq".to_string()),
+            color: Some(ColorTags::bt709()),
+            ..Self::new(VideoCodec::ProRes, None)
+        }
+    }
+
+    // ProRes 4444 with an alpha channel, for compositing in an editor.
+    pub fn alpha() -> Self {
+        Self {
+            profile: Some("4444".to_string()),
+            pixel_format: Some("yuva444p10le".to_string()),
+            color: Some(ColorTags::bt709()),
+            ..Self::new(VideoCodec::ProRes, None)
+        }
+    }
+
+    // VP9 with an alpha channel in a .webm, for overlays on the web.
+    pub fn alpha_webm() -> Self {
+        Self {
+            pixel_format: Some("yuva420p".to_string()),
+            ..Self::new(VideoCodec::Vp9, Some(RateControl::Crf(30)))
+        }
+    }
+
+    pub fn preset(name: &str) -> Result<Self> {
+        match name {
+            "draft" => Ok(Self::draft()),
+            "web" => Ok(Self::web()),
+            "master" => Ok(Self::master()),
+            "alpha" => Ok(Self::alpha()),
+            "alpha-webm" => Ok(Self::alpha_webm()),
+            other => bail!(
+                "unknown encoder preset: {other} (expected one of {})",
+                Self::PRESETS.join(", ")
+            ),
+        }
+    }
+
+    pub fn pixel_format(&self) -> &str {
+        self.pixel_format
+            .as_deref()
+            .unwrap_or(self.codec.default_pixel_format())
+    }
+
+    // Whether the pixel format keeps the frames' alpha channel.
+    pub fn has_alpha(&self) -> bool {
+        let format = self.pixel_format();
+        format.starts_with("yuva")
+            || format.starts_with("gbrap")
+            || format.starts_with("ya")
+            || ["rgba", "bgra", "argb", "abgr"]
+                .iter()
+                .any(|packed| format.starts_with(packed))
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match (self.codec, self.rate) {
+            (VideoCodec::ProRes, Some(_)) => {
+                bail!("prores has no crf/bitrate; pick a quality with `profile`")
+            }
+            (_, Some(RateControl::Bitrate(0))) => bail!("encoder bitrate must be > 0"),
+            (VideoCodec::H264 | VideoCodec::H265, Some(RateControl::Crf(crf))) if crf > 51 => {
+                bail!("x264/x265 crf must be <= 51")
+            }
+            (VideoCodec::Vp9 | VideoCodec::Av1, Some(RateControl::Crf(crf))) if crf > 63 => {
+                bail!("vp9/av1 crf must be <= 63")
+            }
+            _ => {}
+        }
+        if self.gop == Some(0) {
+            bail!("encoder gop must be > 0");
+        }
+        if let Some(seconds) = self.gop_seconds
+            && !(seconds.is_finite() && seconds > 0.0)
+        {
+            bail!("encoder gop_seconds must be > 0");
+        }
+        Ok(())
+    }
+
+    // Keyframe interval in frames at `fps`, if one is set.
+    pub fn gop_frames(&self, fps: u32) -> Option<u32> {
+        self.gop.or_else(|| {
+            self.gop_seconds
+                .map(|seconds| ((seconds * fps as f32).round() as u32).max(1))
+        })
+    }
+
+    // Frames arrive bottom-up, so the filter chain always starts with `vflip`.
+    pub(crate) fn filter(&self) -> String {
+        let mut filter = "vflip".to_string();
+        if let Some(color) = &self.color
+            && let Some(matrix) = color.scale_matrix()
+        {
+            let range = if color.full_range { "full" } else { "tv" };
+            filter.push_str(&format!(",scale=out_color_matrix={matrix}:out_range={range}"));
+        }
+        filter
+    }
+
+    // Everything between the input and the output path, for frames at `fps`.
+    pub(crate) fn output_args(&self, fps: u32) -> Vec<String> {
+        let mut args = vec![
+            "-vf".to_string(),
+            self.filter(),
+            "-c:v".to_string(),
+            self.codec.encoder().to_string(),
+            "-pix_fmt".to_string(),
+            self.pixel_format().to_string(),
+        ];
+        let mut push = |name: &str, value: String| {
+            args.push(name.to_string());
+            args.push(value);
+        };
+
+        match self.rate {
+            Some(RateControl::Crf(crf)) => {
+                push("-crf", crf.to_string());
+                // VP9 only treats -crf as constant quality with no target bitrate.
+                if self.codec == VideoCodec::Vp9 {
+                    push("-b:v", "0".to_string());
+                }
+            }
+            Some(RateControl::Bitrate(kbps)) => push("-b:v", format!("{kbps}k")),
+            None => {}
+        }
+        if let Some(preset) = &self.preset {
+            let name = match self.codec {
+                VideoCodec::Vp9 => "-deadline",
+                _ => "-preset",
+            };
+            push(name, preset.clone());
+        }
+        if let Some(profile) = &self.profile {
+            push("-profile:v", profile.clone());
+        }
+        if let Some(gop) = self.gop_frames(fps) {
+            push("-g", gop.to_string());
+        }
+        if let Some(color) = &self.color {
+            push("-color_primaries", color.primaries.clone());
+            push("-color_trc", color.transfer.clone());
+            push("-colorspace", color.matrix.clone());
+            let range = if color.full_range { "pc" } else { "tv" };
+            push("-color_range", range.to_string());
+        }
+        args.extend(self.extra_args.iter().cloned());
+        args
+    }
+}
+
+// Names of the video encoders the local ffmpeg was built with.
+pub fn available_encoders() -> Result<Vec<String>> {
+    let output = Command::new("ffmpeg")
+        .arg("-hide_banner")
+        .arg("-encoders")
+        .output()
+        .context("failed to run ffmpeg (is it on PATH?)")?;
+    if !output.status.success() {
+        bail!(
+            "ffmpeg -encoders failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(parse_encoders(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// The codecs in `VideoCodec::ALL` whose encoder is available locally.
+pub fn supported_codecs() -> Result<Vec<VideoCodec>> {
+    let encoders = available_encoders()?;
+    Ok(VideoCodec::ALL
+        .into_iter()
+        .filter(|codec| encoders.iter().any(|name| name == codec.encoder()))
+        .collect())
+}
+
+// Lines look like ` V....D libx264    libx264 H.264 ...`, after a legend that
+// ends with a `------` line.
+fn parse_encoders(listing: &str) -> Vec<String> {
+    listing
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("---"))
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let flags = fields.next()?;
+            let name = fields.next()?;
+            flags.starts_with('V').then(|| name.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_containers_with_the_default_first() {
+        assert_eq!(VideoCodec::H264.container(), "mp4");
+        assert_eq!(VideoCodec::Vp9.container(), "webm");
+        assert_eq!(VideoCodec::ProRes.container(), "mov");
+        assert!(VideoCodec::H265.containers().contains(&"mkv"));
+        assert!(VideoCodec::Av1.containers().contains(&"webm"));
+        assert!(!VideoCodec::Vp9.containers().contains(&"mp4"));
+        assert_eq!(VideoCodec::ProRes.containers(), ["mov"]);
+    }
+
+    #[test]
+    fn builds_codec_specific_args() {
+        let args = EncoderConfig::default().output_args(30).join(" ");
+        assert_eq!(args, "-vf vflip -c:v libx264 -pix_fmt yuv420p -crf 18");
+
+        let vp9 = EncoderConfig {
+            preset: Some("good".to_string()),
+            gop: Some(48),
+            ..EncoderConfig::new(VideoCodec::Vp9, Some(RateControl::Crf(32)))
+        };
+        assert_eq!(
+            vp9.output_args(30).join(" "),
+            "-vf vflip -c:v libvpx-vp9 -pix_fmt yuv420p -crf 32 -b:v 0 -deadline good -g 48"
+        );
+
+        let master = EncoderConfig::master().output_args(30).join(" ");
+        assert!(master.contains("-c:v prores_ks -pix_fmt yuv422p10le"), "{master}");
+        assert!(master.contains("scale=out_color_matrix=bt709:out_range=tv"), "{master}");
+        assert!(master.ends_with("-colorspace bt709 -color_range tv"), "{master}");
+
+        for name in EncoderConfig::PRESETS {
+            let config = EncoderConfig::preset(name).unwrap();
+            config.validate().unwrap();
+            assert_eq!(config.has_alpha(), name.starts_with("alpha"), "{name}");
+        }
+        assert!(EncoderConfig::preset("huge").is_err());
+        let prores_crf = EncoderConfig::new(VideoCodec::ProRes, Some(RateControl::Crf(10)));
+        assert!(prores_crf.validate().is_err());
+    }
+
+    #[test]
+    fn web_keyframes_every_two_seconds_at_any_fps() {
+        let web = EncoderConfig::web();
+        assert_eq!(web.gop_frames(30), Some(60));
+        assert_eq!(web.gop_frames(24), Some(48));
+        assert!(web.output_args(60).join(" ").contains("-g 120"));
+
+        let fixed = EncoderConfig {
+            gop: Some(12),
+            ..EncoderConfig::web()
+        };
+        assert_eq!(fixed.gop_frames(60), Some(12));
+        assert_eq!(EncoderConfig::default().gop_frames(30), None);
+
+        let never = EncoderConfig {
+            gop_seconds: Some(0.0),
+            ..EncoderConfig::web()
+        };
+        assert!(never.validate().is_err());
+    }
+
+    #[test]
+    fn parses_encoder_listing() {
+        let listing = "\
+Encoders:
+ V..... = Video
+ A..... = Audio
+ ------
+ V....D libx264              libx264 H.264 / AVC (codec h264)
+ V....D prores_ks            Apple ProRes (iCodec Pro) (codec prores)
+ A....D aac                  AAC (Advanced Audio Coding)
+";
+        assert_eq!(parse_encoders(listing), ["libx264", "prores_ks"]);
+    }
+}
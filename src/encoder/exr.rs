@@ -0,0 +1,132 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+// OpenEXR constants used below; see the OpenEXR file layout spec.
+const MAGIC: [u8; 4] = [0x76, 0x2f, 0x31, 0x01];
+const VERSION: [u8; 4] = [2, 0, 0, 0];
+const PIXEL_FLOAT: i32 = 2;
+// Channels are stored in alphabetical order.
+const CHANNELS: [&str; 4] = ["A", "B", "G", "R"];
+
+// Writes a top-down RGBA8 frame as an uncompressed scanline EXR with 32-bit
+// float channels. Color is converted from sRGB to linear and premultiplied by
+// alpha, which is what compositors expect from EXR.
+pub fn write_exr(path: &Path, rgba: &[u8], width: u32, height: u32) -> Result<()> {
+    let file = File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+    let mut out = BufWriter::new(file);
+    out.write_all(&encode_exr(rgba, width, height))
+        .and_then(|_| out.flush())
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+fn encode_exr(rgba: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let mut header = Vec::new();
+    header.extend_from_slice(&MAGIC);
+    header.extend_from_slice(&VERSION);
+
+    let mut channels = Vec::new();
+    for name in CHANNELS {
+        channels.extend_from_slice(name.as_bytes());
+        channels.push(0);
+        channels.extend_from_slice(&PIXEL_FLOAT.to_le_bytes());
+        // pLinear and three reserved bytes, then x/y sampling.
+        channels.extend_from_slice(&[0, 0, 0, 0]);
+        channels.extend_from_slice(&1i32.to_le_bytes());
+        channels.extend_from_slice(&1i32.to_le_bytes());
+    }
+    channels.push(0);
+
+    let mut window = Vec::new();
+    for value in [0, 0, width as i32 - 1, height as i32 - 1] {
+        window.extend_from_slice(&value.to_le_bytes());
+    }
+
+    attribute(&mut header, "channels", "chlist", &channels);
+    attribute(&mut header, "compression", "compression", &[0]);
+    attribute(&mut header, "dataWindow", "box2i", &window);
+    attribute(&mut header, "displayWindow", "box2i", &window);
+    attribute(&mut header, "lineOrder", "lineOrder", &[0]);
+    attribute(&mut header, "pixelAspectRatio", "float", &1f32.to_le_bytes());
+    attribute(&mut header, "screenWindowCenter", "v2f", &[0; 8]);
+    attribute(&mut header, "screenWindowWidth", "float", &1f32.to_le_bytes());
+    header.push(0);
+
+    // One scanline per block: an offset table, then `y`, byte count and each
+    // channel's row of floats.
+    let row_bytes = width as usize * CHANNELS.len() * 4;
+    let block_size = 8 + row_bytes;
+    let table_end = header.len() + height as usize * 8;
+    let mut exr = Vec::with_capacity(table_end + height as usize * block_size);
+    exr.extend_from_slice(&header);
+    for y in 0..height as usize {
+        exr.extend_from_slice(&((table_end + y * block_size) as u64).to_le_bytes());
+    }
+
+    let linear: Vec<f32> = (0..=255u8).map(|c| srgb_to_linear(c as f32 / 255.0)).collect();
+    for (y, row) in rgba.chunks_exact(width as usize * 4).enumerate() {
+        exr.extend_from_slice(&(y as i32).to_le_bytes());
+        exr.extend_from_slice(&(row_bytes as i32).to_le_bytes());
+        // A, B, G, R
+        for channel in [3, 2, 1, 0] {
+            for px in row.chunks_exact(4) {
+                let alpha = px[3] as f32 / 255.0;
+                let value = if channel == 3 {
+                    alpha
+                } else {
+                    linear[px[channel] as usize] * alpha
+                };
+                exr.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+    }
+    exr
+}
+
+fn attribute(header: &mut Vec<u8>, name: &str, kind: &str, value: &[u8]) {
+    header.extend_from_slice(name.as_bytes());
+    header.push(0);
+    header.extend_from_slice(kind.as_bytes());
+    header.push(0);
+    header.extend_from_slice(&(value.len() as i32).to_le_bytes());
+    header.extend_from_slice(value);
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_linear_premultiplied_scanlines() {
+        // 2x1: opaque white, then half-transparent sRGB mid-gray.
+        let exr = encode_exr(&[255, 255, 255, 255, 128, 128, 128, 128], 2, 1);
+        assert_eq!(exr[..4], MAGIC);
+
+        let read_i32 = |at: usize| i32::from_le_bytes(exr[at..at + 4].try_into().unwrap());
+        let read_f32 = |at: usize| f32::from_le_bytes(exr[at..at + 4].try_into().unwrap());
+        let table = exr.len() - (8 + 2 * 4 * 4) - 8;
+        let block = u64::from_le_bytes(exr[table..table + 8].try_into().unwrap()) as usize;
+        assert_eq!(block, table + 8);
+        assert_eq!(read_i32(block), 0);
+        assert_eq!(read_i32(block + 4), 32);
+
+        // Channel rows A, B, G, R, two pixels each.
+        let data = block + 8;
+        assert_eq!(read_f32(data), 1.0);
+        assert!((read_f32(data + 4) - 128.0 / 255.0).abs() < 1e-6);
+        assert!((read_f32(data + 8) - 1.0).abs() < 1e-6);
+        let gray = read_f32(data + 12);
+        assert!((gray - 0.2158 * 128.0 / 255.0).abs() < 1e-3, "{gray}");
+        assert_eq!(read_f32(data + 24), read_f32(data + 8));
+    }
+}
@@ -4,68 +4,35 @@ use std::process::{Command, Stdio};
 
 use anyhow::{bail, Context, Result};
 
-use crate::audio::{MusicTrack, SfxEvent};
+use crate::audio::{
+    mix_timeline_audio, normalize_loudness, write_wav, LoudnessReport, LoudnessTarget, MusicTrack,
+    SfxEvent, DEFAULT_SAMPLE_RATE,
+};
 
+// Mixes music and SFX in-process (see `audio::mixer`) and writes a 16-bit WAV.
 pub fn render_audio_wav(
-    music: &MusicTrack,
+    music: &[MusicTrack],
     sfx: &[SfxEvent],
     duration: f32,
     output_wav: &Path,
 ) -> Result<()> {
-    if sfx.len() > 100 {
-        eprintln!("warning: high SFX event count: {}", sfx.len());
-    }
-
-    let mut cmd = Command::new("ffmpeg");
-    cmd.arg("-y")
-        .arg("-loglevel")
-        .arg("error")
-        .arg("-stream_loop")
-        .arg(if music.looped { "-1" } else { "0" })
-        .arg("-i")
-        .arg(&music.path);
-
-    if !sfx.is_empty() {
-        let sfx_path = &sfx[0].path;
-        cmd.arg("-i").arg(sfx_path);
-        let filter = build_sfx_filter(sfx, music.volume);
-        cmd.arg("-filter_complex")
-            .arg(filter)
-            .arg("-map")
-            .arg("[aout]");
-    } else {
-        cmd.arg("-map").arg("0:a:0");
-    }
-
-    cmd.arg("-t")
-        .arg(format!("{duration:.3}"))
-        .arg("-c:a")
-        .arg("pcm_s16le")
-        .arg(output_wav)
-        .stdout(Stdio::null())
-        .stderr(Stdio::piped());
-
-    let mut child = cmd
-        .spawn()
-        .context("failed to spawn ffmpeg for audio render")?;
-    let status = child
-        .wait()
-        .context("failed to wait for ffmpeg audio render")?;
-
-    if !status.success() {
-        let stderr = child
-            .stderr
-            .take()
-            .map(|mut s| {
-                let mut buf = String::new();
-                let _ = s.read_to_string(&mut buf);
-                buf
-            })
-            .unwrap_or_default();
-        bail!("ffmpeg audio render failed with status {}: {}", status, stderr.trim());
-    }
+    let mixed = mix_timeline_audio(music, sfx, duration, DEFAULT_SAMPLE_RATE)?;
+    write_wav(&mixed, output_wav)
+}
 
-    Ok(())
+// Like `render_audio_wav`, normalized to `target` with a true-peak limiter.
+// Returns the before/after measurements for the render summary.
+pub fn render_audio_wav_normalized(
+    music: &[MusicTrack],
+    sfx: &[SfxEvent],
+    duration: f32,
+    target: &LoudnessTarget,
+    output_wav: &Path,
+) -> Result<LoudnessReport> {
+    let mut mixed = mix_timeline_audio(music, sfx, duration, DEFAULT_SAMPLE_RATE)?;
+    let report = normalize_loudness(&mut mixed, target);
+    write_wav(&mixed, output_wav)?;
+    Ok(report)
 }
 
 pub fn trim_audio(
@@ -113,6 +80,11 @@ pub fn trim_audio(
 }
 
 pub fn mux_video_audio(video_path: &Path, audio_path: &Path, output_path: &Path) -> Result<()> {
+    // WebM only holds Vorbis/Opus audio.
+    let webm = output_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("webm"));
+    let audio_codec = if webm { "libopus" } else { "aac" };
     let mut cmd = Command::new("ffmpeg");
     cmd.arg("-y")
         .arg("-loglevel")
@@ -124,7 +96,7 @@ pub fn mux_video_audio(video_path: &Path, audio_path: &Path, output_path: &Path)
         .arg("-c:v")
         .arg("copy")
         .arg("-c:a")
-        .arg("aac")
+        .arg(audio_codec)
         .arg("-shortest")
         .arg(output_path)
         .stdout(Stdio::null())
@@ -149,31 +121,3 @@ pub fn mux_video_audio(video_path: &Path, audio_path: &Path, output_path: &Path)
 
     Ok(())
 }
-
-fn build_sfx_filter(sfx: &[SfxEvent], music_volume: f32) -> String {
-    let split_count = sfx.len();
-    let mut filter = String::new();
-
-    filter.push_str(&format!("[1:a]asplit={}", split_count));
-    for i in 0..split_count {
-        filter.push_str(&format!("[b{}]", i));
-    }
-    filter.push(';');
-
-    for (i, event) in sfx.iter().enumerate() {
-        let delay_ms = (event.time * 1000.0).round() as i64;
-        filter.push_str(&format!(
-            "[b{0}]adelay={1}|{1},volume={2}[bd{0}];",
-            i, delay_ms, event.volume
-        ));
-    }
-
-    filter.push_str(&format!("[0:a]volume={}[bg];", music_volume));
-    filter.push_str("[bg]");
-    for i in 0..split_count {
-        filter.push_str(&format!("[bd{}]", i));
-    }
-    filter.push_str(&format!("amix=inputs={}:normalize=0[aout]", split_count + 1));
-
-    filter
-}
@@ -4,6 +4,8 @@ use std::process::{Child, ChildStdin, Command, Stdio};
 
 use anyhow::{bail, Context, Result};
 
+use crate::encoder::EncoderConfig;
+
 pub struct FfmpegVideoEncoder {
     child: Child,
     stdin: Option<ChildStdin>,
@@ -13,6 +15,17 @@ pub struct FfmpegVideoEncoder {
 
 impl FfmpegVideoEncoder {
     pub fn start(width: u32, height: u32, fps: u32, output_path: &Path) -> Result<Self> {
+        Self::start_with(width, height, fps, &EncoderConfig::default(), output_path)
+    }
+
+    pub fn start_with(
+        width: u32,
+        height: u32,
+        fps: u32,
+        config: &EncoderConfig,
+        output_path: &Path,
+    ) -> Result<Self> {
+        config.validate()?;
         let mut cmd = Command::new("ffmpeg");
         cmd.arg("-y")
             .arg("-loglevel")
@@ -27,14 +40,7 @@ impl FfmpegVideoEncoder {
             .arg(fps.to_string())
             .arg("-i")
             .arg("-")
-            .arg("-vf")
-            .arg("vflip")
-            .arg("-c:v")
-            .arg("libx264")
-            .arg("-pix_fmt")
-            .arg("yuv420p")
-            .arg("-crf")
-            .arg("18")
+            .args(config.output_args(fps))
             .arg(output_path)
             .stdin(Stdio::piped())
             .stdout(Stdio::null())
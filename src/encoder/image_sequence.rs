@@ -0,0 +1,253 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::encoder::exr::write_exr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SequenceFormat {
+    // 8-bit straight-alpha RGBA.
+    #[default]
+    Png,
+    // 32-bit float, linear and premultiplied, for compositing.
+    Exr,
+}
+
+impl SequenceFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            SequenceFormat::Png => "png",
+            SequenceFormat::Exr => "exr",
+        }
+    }
+}
+
+// Writes renderer frames as numbered images (`<prefix>_00000.png`, ...),
+// keeping the alpha channel. Same start/write_frame/finish shape as
+// `FfmpegVideoEncoder`, so render loops can target either.
+pub struct ImageSequenceWriter {
+    dir: PathBuf,
+    prefix: String,
+    format: SequenceFormat,
+    width: u32,
+    height: u32,
+    first_number: u32,
+    frames: u32,
+}
+
+impl ImageSequenceWriter {
+    // Writes PNGs unless `with_format` picks another format.
+    pub fn start(width: u32, height: u32, dir: &Path, prefix: &str) -> Result<Self> {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            prefix: prefix.to_string(),
+            format: SequenceFormat::Png,
+            width,
+            height,
+            first_number: 0,
+            frames: 0,
+        })
+    }
+
+    pub fn with_format(mut self, format: SequenceFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    // Numbers files from `number` instead of 0, e.g. the timeline frame index
+    // of the first rendered frame.
+    pub fn with_first_number(mut self, number: u32) -> Self {
+        self.first_number = number;
+        self
+    }
+
+    pub fn frame_path(&self, number: u32) -> PathBuf {
+        self.dir
+            .join(format!("{}_{number:05}.{}", self.prefix, self.format.extension()))
+    }
+
+    // `frame` is bottom-up, as returned by `Renderer::render_scene_to_rgba`.
+    pub fn write_frame(&mut self, frame: &[u8]) -> Result<()> {
+        let expected = (self.width * self.height * 4) as usize;
+        if frame.len() != expected {
+            bail!(
+                "frame size mismatch: got {}, expected {}",
+                frame.len(),
+                expected
+            );
+        }
+        let path = self.frame_path(self.first_number + self.frames);
+        let top_down = flip_rows(frame, self.width);
+        match self.format {
+            SequenceFormat::Png => image::save_buffer_with_format(
+                &path,
+                &top_down,
+                self.width,
+                self.height,
+                image::ExtendedColorType::Rgba8,
+                image::ImageFormat::Png,
+            )
+            .with_context(|| format!("failed to write {}", path.display()))?,
+            SequenceFormat::Exr => write_exr(&path, &top_down, self.width, self.height)?,
+        }
+        self.frames += 1;
+        Ok(())
+    }
+
+    // Returns the number of frames written.
+    pub fn finish(self) -> Result<u32> {
+        Ok(self.frames)
+    }
+}
+
+// Reverses the row order of an RGBA frame: bottom-up to top-down and back.
+pub fn flip_rows(rgba: &[u8], width: u32) -> Vec<u8> {
+    let row = (width * 4) as usize;
+    let mut flipped = Vec::with_capacity(rgba.len());
+    for chunk in rgba.chunks_exact(row).rev() {
+        flipped.extend_from_slice(chunk);
+    }
+    flipped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use std::process::{Command, Stdio};
+
+    use super::*;
+    use crate::backend::renderer::Renderer;
+    use crate::backend::software::SoftwareRender;
+    use crate::encoder::{supported_codecs, EncoderConfig, FfmpegVideoEncoder};
+    use crate::scene::{Color, Object, Shape, Transform, Vec2};
+    use crate::timeline::{SampledClip, SampledLayer, SampledScene};
+
+    const W: u32 = 64;
+    const H: u32 = 48;
+
+    // An opaque blue square under a half-transparent red one, on a transparent
+    // background.
+    fn render_overlay() -> Vec<u8> {
+        let rect = |x: f32, color: Color| SampledClip {
+            object: Object::Shape(Shape::Rect {
+                width: 24.0,
+                height: 24.0,
+                color,
+            }),
+            transform: Transform {
+                pos: Vec2 { x, y: 0.0 },
+                ..Transform::default()
+            },
+        };
+        let scene = SampledScene {
+            layers: vec![SampledLayer {
+                name: "overlay".to_string(),
+                clips: vec![
+                    rect(-8.0, Color::rgb(0, 0, 255)),
+                    rect(8.0, Color::rgba(255, 0, 0, 128)),
+                ],
+            }],
+        };
+        let mut renderer = SoftwareRender::new(W, H, Color::TRANSPARENT).unwrap();
+        flip_rows(&renderer.render_scene_to_rgba(&scene).unwrap(), W)
+    }
+
+    fn pixel(top_down: &[u8], x: u32, y: u32) -> [u8; 4] {
+        let idx = ((y * W + x) * 4) as usize;
+        top_down[idx..idx + 4].try_into().unwrap()
+    }
+
+    // Background, opaque blue, red over blue, and red over nothing.
+    const PROBES: [(u32, u32); 4] = [(2, 2), (18, 24), (32, 24), (46, 24)];
+
+    #[test]
+    fn alpha_survives_png_round_trip() {
+        let frame = render_overlay();
+        assert_eq!(pixel(&frame, 2, 2)[3], 0);
+        assert_eq!(pixel(&frame, 18, 24), [0, 0, 255, 255]);
+        assert_eq!(pixel(&frame, 32, 24)[3], 255);
+        // Straight alpha: full red at half coverage, not darkened.
+        assert_eq!(pixel(&frame, 46, 24), [255, 0, 0, 128]);
+
+        let dir = std::env::temp_dir().join(format!("s2s-alpha-png-{}", std::process::id()));
+        let mut writer = ImageSequenceWriter::start(W, H, &dir, "frame")
+            .unwrap()
+            .with_first_number(10);
+        writer.write_frame(&flip_rows(&frame, W)).unwrap();
+        writer.write_frame(&flip_rows(&frame, W)).unwrap();
+        let path = writer.frame_path(11);
+        assert!(path.ends_with("frame_00011.png"));
+        assert_eq!(writer.finish().unwrap(), 2);
+
+        let decoded = image::open(&path).unwrap();
+        assert!(decoded.color().has_alpha());
+        assert_eq!(decoded.to_rgba8().into_raw(), frame);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    // Needs ffmpeg with prores_ks / libvpx-vp9; skipped when they're missing.
+    #[test]
+    fn alpha_survives_video_round_trip() {
+        let Ok(supported) = supported_codecs() else {
+            return;
+        };
+        let frame = render_overlay();
+        let dir = std::env::temp_dir().join(format!("s2s-alpha-video-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for config in [EncoderConfig::alpha(), EncoderConfig::alpha_webm()] {
+            if !supported.contains(&config.codec) {
+                continue;
+            }
+            let path = dir.join(format!("overlay.{}", config.codec.container()));
+            let mut encoder = FfmpegVideoEncoder::start_with(W, H, 30, &config, &path).unwrap();
+            for _ in 0..3 {
+                encoder.write_frame(&flip_rows(&frame, W)).unwrap();
+            }
+            encoder.finish().unwrap();
+
+            let decoded = decode_first_frame(&path, config.codec.encoder());
+            for (x, y) in PROBES {
+                let (want, got) = (pixel(&frame, x, y), pixel(&decoded, x, y));
+                for c in 0..4 {
+                    assert!(
+                        want[c].abs_diff(got[c]) <= 12,
+                        "{path:?} at ({x}, {y}): {want:?} vs {got:?}",
+                        path = path.display()
+                    );
+                }
+            }
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    fn decode_first_frame(path: &Path, encoder: &str) -> Vec<u8> {
+        let mut cmd = Command::new("ffmpeg");
+        cmd.arg("-loglevel").arg("error");
+        // ffmpeg's native VP9 decoder drops the alpha side channel.
+        if encoder == "libvpx-vp9" {
+            cmd.arg("-c:v").arg("libvpx-vp9");
+        }
+        let mut child = cmd
+            .arg("-i")
+            .arg(path)
+            .arg("-frames:v")
+            .arg("1")
+            .arg("-f")
+            .arg("rawvideo")
+            .arg("-pix_fmt")
+            .arg("rgba")
+            .arg("-")
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut out = Vec::new();
+        child.stdout.take().unwrap().read_to_end(&mut out).unwrap();
+        assert!(child.wait().unwrap().success());
+        assert_eq!(out.len(), (W * H * 4) as usize);
+        out
+    }
+}
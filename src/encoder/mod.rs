@@ -1,7 +1,23 @@
+pub mod animated;
+pub mod config;
+pub mod exr;
 pub mod ffmpeg_video;
 pub mod ffmpeg_audio;
+pub mod image_sequence;
+pub mod sink;
 pub mod video_clips;
 
+pub use animated::{
+    AnimatedFormat, AnimatedImageConfig, AnimatedImageEncoder, Dither, PaletteMode,
+};
+pub use config::{
+    available_encoders, supported_codecs, ColorTags, EncoderConfig, RateControl, VideoCodec,
+};
 pub use ffmpeg_video::FfmpegVideoEncoder;
-pub use ffmpeg_audio::{mux_video_audio, render_audio_wav, trim_audio};
+pub use exr::write_exr;
+pub use image_sequence::{flip_rows, ImageSequenceWriter, SequenceFormat};
+pub use sink::FrameSink;
+pub use ffmpeg_audio::{
+    mux_video_audio, render_audio_wav, render_audio_wav_normalized, trim_audio,
+};
 pub use video_clips::{build_base_video, ffprobe_metadata, normalize_if_needed, VideoMetadata};
@@ -0,0 +1,35 @@
+use anyhow::Result;
+
+use crate::encoder::{AnimatedImageEncoder, FfmpegVideoEncoder, ImageSequenceWriter};
+
+// Anything that consumes bottom-up RGBA frames in order, so render loops can
+// target any output. `Renderer::render_timeline_pipelined` drives a sink from
+// its own encode thread.
+pub trait FrameSink {
+    fn write_frame(&mut self, frame: &[u8]) -> Result<()>;
+}
+
+impl FrameSink for FfmpegVideoEncoder {
+    fn write_frame(&mut self, frame: &[u8]) -> Result<()> {
+        FfmpegVideoEncoder::write_frame(self, frame)
+    }
+}
+
+impl FrameSink for AnimatedImageEncoder {
+    fn write_frame(&mut self, frame: &[u8]) -> Result<()> {
+        AnimatedImageEncoder::write_frame(self, frame)
+    }
+}
+
+impl FrameSink for ImageSequenceWriter {
+    fn write_frame(&mut self, frame: &[u8]) -> Result<()> {
+        ImageSequenceWriter::write_frame(self, frame)
+    }
+}
+
+impl FrameSink for Vec<Vec<u8>> {
+    fn write_frame(&mut self, frame: &[u8]) -> Result<()> {
+        self.push(frame.to_vec());
+        Ok(())
+    }
+}
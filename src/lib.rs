@@ -1,20 +1,32 @@
 pub mod audio;
 pub mod backend;
 pub mod encoder;
+pub mod project;
 pub mod scene;
 pub mod timeline;
 pub mod video;
 
-pub use audio::{AudioEngine, MusicTrack, SfxEvent};
+pub use audio::{
+    AudioEngine, DialogueClip, LoudnessTarget, MusicTrack, PreviewAudio, SfxEvent, SfxVariation,
+};
 pub use backend::raylib_preview::RaylibPreview;
+pub use backend::pipeline::{PipelineOptions, RenderStats};
 pub use backend::raylib_render::{RaylibRender, RenderProgress};
+pub use backend::renderer::Renderer;
+pub use backend::software::SoftwareRender;
+pub use backend::stills::ContactSheet;
+pub use backend::text_layout::TextMetrics;
 pub use encoder::{
-    build_base_video, mux_video_audio, render_audio_wav, trim_audio, FfmpegVideoEncoder,
+    build_base_video, mux_video_audio, render_audio_wav, render_audio_wav_normalized, trim_audio,
+    AnimatedImageConfig, AnimatedImageEncoder, EncoderConfig, FfmpegVideoEncoder, FrameSink,
+    ImageSequenceWriter, SequenceFormat, VideoCodec,
 };
+pub use project::{Project, ProjectFormat};
 pub use video::{resolve_segments, VideoClip, VideoSegment};
 pub use scene::{
-    AnimatedTransform, Color, Easing, FontFamily, FontSource, ImageObject, Keyframe, Object, Shape,
-    StyleFlags, StyledText, TextObject, TextRun, Track, Transform, Vec2,
+    AnimatedAnchor, AnimatedTransform, Anchor, AnchorMode, Color, Easing, FontFamily, FontSource,
+    Group, ImageObject, Interpolation, Keyframe, Lerp, Object, Shape, StyleFlags, StyledText,
+    TextAlign, TextObject, TextRun, Track, TrackCursor, Transform, Vec2, VerticalAlign,
 };
 pub use timeline::{Clip, Layer, Timeline};
 pub use raylib::consts::TraceLogLevel;
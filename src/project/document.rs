@@ -0,0 +1,232 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::audio::{DialogueClip, Ducking, LoudnessTarget, MixOptions, MusicTrack, SfxEvent};
+use crate::scene::{FontSource, Object};
+use crate::timeline::Timeline;
+use crate::video::VideoClip;
+
+// Everything needed to render a video from data: the visual timeline plus the
+// audio and base-video sections that are otherwise passed to the encoders.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Project {
+    pub timeline: Timeline,
+    #[serde(default)]
+    pub music: Vec<MusicTrack>,
+    #[serde(default)]
+    pub dialogue: Vec<DialogueClip>,
+    #[serde(default)]
+    pub sfx: Vec<SfxEvent>,
+    // Lowers the music under SFX/voice in the offline mix.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ducking: Option<Ducking>,
+    // Integrated loudness and true-peak ceiling for the final mix.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loudness: Option<LoudnessTarget>,
+    #[serde(default)]
+    pub base_video: Vec<VideoClip>,
+}
+
+impl Project {
+    pub fn new(timeline: Timeline) -> Self {
+        Self {
+            timeline,
+            music: Vec::new(),
+            dialogue: Vec::new(),
+            sfx: Vec::new(),
+            ducking: None,
+            loudness: None,
+            base_video: Vec::new(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let duration = self.timeline.duration;
+        if !(duration.is_finite() && duration > 0.0) {
+            bail!("timeline duration must be finite and > 0");
+        }
+        if self.timeline.fps == 0 {
+            bail!("timeline fps must be > 0");
+        }
+
+        for layer in &self.timeline.layers {
+            for (idx, clip) in layer.clips.iter().enumerate() {
+                let context = || format!("layer '{}' clip {}", layer.name, idx);
+                clip.validate_against(duration).with_context(context)?;
+                validate_object_assets(&clip.object).with_context(context)?;
+            }
+        }
+
+        for (idx, track) in self.music.iter().enumerate() {
+            validate_music(track, duration).with_context(|| format!("music track {idx}"))?;
+        }
+
+        for (idx, clip) in self.dialogue.iter().enumerate() {
+            clip.validate_against(duration)
+                .with_context(|| format!("dialogue clip {idx}"))?;
+        }
+
+        for (idx, event) in self.sfx.iter().enumerate() {
+            validate_sfx(event, duration).with_context(|| format!("sfx event {idx}"))?;
+        }
+
+        if let Some(ducking) = &self.ducking {
+            ducking.validate().context("ducking")?;
+        }
+        if let Some(loudness) = &self.loudness {
+            loudness.validate().context("loudness")?;
+        }
+
+        for (idx, clip) in self.base_video.iter().enumerate() {
+            let context = || format!("base video clip {idx}");
+            clip.validate().with_context(context)?;
+            if clip.end_time > duration {
+                bail!("{}: clip ends after timeline duration", context());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn mix_options(&self) -> MixOptions {
+        MixOptions {
+            ducking: self.ducking.clone(),
+            loudness: self.loudness,
+        }
+    }
+
+    // Rewrites relative asset paths so they are relative to `base_dir` (usually
+    // the directory of the project file) instead of the process working dir.
+    pub fn resolve_paths(&mut self, base_dir: &Path) {
+        for layer in &mut self.timeline.layers {
+            for clip in &mut layer.clips {
+                resolve_object_paths(&mut clip.object, base_dir);
+            }
+        }
+        for track in &mut self.music {
+            resolve(&mut track.path, base_dir);
+        }
+        for clip in &mut self.dialogue {
+            resolve(&mut clip.path, base_dir);
+        }
+        for event in &mut self.sfx {
+            resolve(&mut event.path, base_dir);
+        }
+        for clip in &mut self.base_video {
+            resolve(&mut clip.path, base_dir);
+        }
+    }
+}
+
+fn resolve_object_paths(object: &mut Object, base_dir: &Path) {
+    match object {
+        Object::Image(image) => resolve(&mut image.path, base_dir),
+        Object::Text(text) => {
+            for source in [
+                Some(&mut text.font.regular),
+                text.font.bold.as_mut(),
+                text.font.italic.as_mut(),
+                text.font.bold_italic.as_mut(),
+            ]
+            .into_iter()
+            .flatten()
+            {
+                if let FontSource::Path(path) = source {
+                    resolve(path, base_dir);
+                }
+            }
+        }
+        Object::Group(group) => {
+            for child in &mut group.children {
+                resolve_object_paths(&mut child.object, base_dir);
+            }
+        }
+        Object::Shape(_) => {}
+    }
+}
+
+fn resolve(path: &mut PathBuf, base_dir: &Path) {
+    if path.is_relative() {
+        *path = base_dir.join(&*path);
+    }
+}
+
+fn validate_object_assets(object: &Object) -> Result<()> {
+    match object {
+        Object::Image(image) => {
+            if !image.path.exists() {
+                bail!("image asset not found: {}", image.path.display());
+            }
+        }
+        Object::Text(text) => {
+            for source in [
+                Some(&text.font.regular),
+                text.font.bold.as_ref(),
+                text.font.italic.as_ref(),
+                text.font.bold_italic.as_ref(),
+            ]
+            .into_iter()
+            .flatten()
+            {
+                if let FontSource::Path(path) = source
+                    && !path.exists()
+                {
+                    bail!("font not found: {}", path.display());
+                }
+            }
+        }
+        Object::Group(group) => {
+            for (idx, child) in group.children.iter().enumerate() {
+                validate_object_assets(&child.object)
+                    .with_context(|| format!("group child {idx}"))?;
+            }
+        }
+        Object::Shape(_) => {}
+    }
+    Ok(())
+}
+
+fn validate_music(music: &MusicTrack, duration: f32) -> Result<()> {
+    if !music.path.exists() {
+        bail!("music not found: {}", music.path.display());
+    }
+    if music.start < 0.0 || music.end <= music.start || music.end > duration {
+        bail!("music bounds must satisfy 0 <= start < end <= duration");
+    }
+    if music.source_offset < 0.0 {
+        bail!("music source_offset must be >= 0");
+    }
+    if music.volume < 0.0 {
+        bail!("music volume must be >= 0");
+    }
+    if music.fade_in < 0.0 || music.fade_out < 0.0 || music.crossfade < 0.0 {
+        bail!("music fades and crossfade must be >= 0");
+    }
+    if music.fade_in + music.fade_out > music.end - music.start {
+        bail!("music fade_in + fade_out must fit between start and end");
+    }
+    Ok(())
+}
+
+fn validate_sfx(event: &SfxEvent, duration: f32) -> Result<()> {
+    if !event.path.exists() {
+        bail!("sfx not found: {}", event.path.display());
+    }
+    if event.time < 0.0 || event.time > duration {
+        bail!("sfx time must be within 0..=duration");
+    }
+    if event.volume < 0.0 {
+        bail!("sfx volume must be >= 0");
+    }
+    if event.rate <= 0.0 {
+        bail!("sfx rate must be > 0");
+    }
+    if let Some(variation) = &event.variation
+        && (variation.pitch < 0.0 || variation.volume_db < 0.0)
+    {
+        bail!("sfx variation ranges must be >= 0");
+    }
+    Ok(())
+}
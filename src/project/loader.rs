@@ -0,0 +1,203 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use crate::project::Project;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectFormat {
+    Json,
+    Ron,
+}
+
+impl ProjectFormat {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        match ext.as_str() {
+            "json" => Ok(ProjectFormat::Json),
+            "ron" => Ok(ProjectFormat::Ron),
+            _ => bail!(
+                "unsupported project file extension (expected .json or .ron): {}",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl Project {
+    // Reads, parses and validates a project file. Relative asset paths are
+    // resolved against the file's directory.
+    pub fn load(path: &Path) -> Result<Self> {
+        let format = ProjectFormat::from_path(path)?;
+        let input = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read project file: {}", path.display()))?;
+        let mut project = Self::parse(&input, format)
+            .with_context(|| format!("failed to parse project file: {}", path.display()))?;
+
+        if let Some(base_dir) = path.parent() {
+            project.resolve_paths(base_dir);
+        }
+        project
+            .validate()
+            .with_context(|| format!("invalid project file: {}", path.display()))?;
+        Ok(project)
+    }
+
+    // Parses without validating or resolving paths; call `validate` before rendering.
+    pub fn parse(input: &str, format: ProjectFormat) -> Result<Self> {
+        let project = match format {
+            ProjectFormat::Json => serde_json::from_str(input)?,
+            ProjectFormat::Ron => ron::from_str(input)?,
+        };
+        Ok(project)
+    }
+
+    pub fn to_string_pretty(&self, format: ProjectFormat) -> Result<String> {
+        let output = match format {
+            ProjectFormat::Json => serde_json::to_string_pretty(self)?,
+            ProjectFormat::Ron => ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?,
+        };
+        Ok(output)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let output = self.to_string_pretty(ProjectFormat::from_path(path)?)?;
+        std::fs::write(path, output)
+            .with_context(|| format!("failed to write project file: {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELLO: &str = "examples/projects/hello.json";
+
+    fn shape_project(clip: &str) -> String {
+        format!(
+            r#"{{ "timeline": {{ "duration": 2.0, "fps": 30, "layers": [
+                {{ "name": "main", "clips": [{clip}] }}
+            ] }} }}"#
+        )
+    }
+
+    fn error_chain(err: anyhow::Error) -> String {
+        format!("{err:#}")
+    }
+
+    #[test]
+    fn round_trips_json_and_ron() {
+        let project = Project::load(Path::new(HELLO)).unwrap();
+        assert_eq!(project.timeline.layers.len(), 2);
+        assert!(project.music[0].path.ends_with("assets/background.mp3"));
+
+        for format in [ProjectFormat::Json, ProjectFormat::Ron] {
+            let text = project.to_string_pretty(format).unwrap();
+            let parsed = Project::parse(&text, format).unwrap();
+            assert_eq!(parsed, project, "{format:?}");
+            parsed.validate().unwrap();
+        }
+    }
+
+    #[test]
+    fn rejects_missing_assets() {
+        let input = shape_project(
+            r#"{ "start": 0.0, "end": 1.0, "object": { "Image": { "path": "missing/logo.png" } } }"#,
+        );
+        let project = Project::parse(&input, ProjectFormat::Json).unwrap();
+        let err = error_chain(project.validate().unwrap_err());
+        assert!(err.contains("layer 'main' clip 0"), "{err}");
+        assert!(err.contains("image asset not found"), "{err}");
+    }
+
+    #[test]
+    fn rejects_clips_outside_the_timeline() {
+        let rect = r#"{ "Shape": { "Rect": { "width": 1.0, "height": 1.0, "color": { "r": 0, "g": 0, "b": 0 } } } }"#;
+        for (start, end) in [(0.0, 3.0), (-1.0, 1.0), (1.0, 1.0)] {
+            let clip = format!(r#"{{ "start": {start:?}, "end": {end:?}, "object": {rect} }}"#);
+            let project = Project::parse(&shape_project(&clip), ProjectFormat::Json).unwrap();
+            assert!(project.validate().is_err(), "{start}..{end}");
+        }
+    }
+
+    #[test]
+    fn rejects_non_finite_timeline_duration() {
+        for duration in ["NaN", "inf"] {
+            let ron = format!("(timeline: (duration: {duration}, fps: 30, layers: []))");
+            let project = Project::parse(&ron, ProjectFormat::Ron).unwrap();
+            let err = error_chain(project.validate().unwrap_err());
+            assert!(err.contains("timeline duration must be finite"), "{duration}: {err}");
+        }
+    }
+
+    #[test]
+    fn rejects_unordered_and_non_finite_keyframes() {
+        let clip = r#"{ "start": 0.0, "end": 1.0,
+            "object": { "Shape": { "Circle": { "radius": 1.0, "color": { "r": 0, "g": 0, "b": 0 } } } },
+            "transform": { "opacity": [
+                { "time": 1.0, "value": 1.0 },
+                { "time": 0.5, "value": 0.0 }
+            ] } }"#;
+        let err = error_chain(Project::parse(&shape_project(clip), ProjectFormat::Json).unwrap_err());
+        assert!(err.contains("strictly increasing"), "{err}");
+
+        let ron = r#"(
+            timeline: (duration: 2.0, fps: 30, layers: [(name: "main", clips: [(
+                start: 0.0,
+                end: 1.0,
+                object: Shape(Circle(radius: 1.0, color: (r: 0, g: 0, b: 0))),
+                transform: (opacity: [(time: NaN, value: 1.0)]),
+            )])]),
+        )"#;
+        let err = error_chain(Project::parse(ron, ProjectFormat::Ron).unwrap_err());
+        assert!(err.contains("finite"), "{err}");
+    }
+
+    #[test]
+    fn rejects_untrimmed_dialogue_that_overruns_the_timeline() {
+        let dir = std::env::temp_dir().join(format!("s2s-loader-dialogue-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let line = dir.join("line.wav");
+        let second = crate::audio::AudioBuffer::silence(100, 100);
+        crate::audio::write_wav(&second, &line).unwrap();
+
+        let project = |dialogue: &str| {
+            let input = format!(
+                r#"{{ "timeline": {{ "duration": 2.0, "fps": 30, "layers": [] }},
+                    "dialogue": [{dialogue}] }}"#
+            );
+            Project::parse(&input, ProjectFormat::Json).unwrap()
+        };
+        let path = serde_json::to_string(&line).unwrap();
+        let fits = project(&format!(r#"{{ "path": {path}, "start_time": 0.5 }}"#));
+        let overruns = project(&format!(r#"{{ "path": {path}, "start_time": 1.5 }}"#));
+        let trimmed = project(&format!(
+            r#"{{ "path": {path}, "start_time": 1.5, "trim_start": 0.6 }}"#
+        ));
+        let past_end = project(&format!(
+            r#"{{ "path": {path}, "start_time": 0.0, "trim_start": 1.0 }}"#
+        ));
+        let results = [fits, overruns, trimmed, past_end].map(|project| project.validate());
+        std::fs::remove_dir_all(&dir).ok();
+
+        let [fits, overruns, trimmed, past_end] = results;
+        fits.unwrap();
+        let err = error_chain(overruns.unwrap_err());
+        assert!(err.contains("dialogue clip 0"), "{err}");
+        assert!(err.contains("after timeline duration"), "{err}");
+        trimmed.unwrap();
+        assert!(error_chain(past_end.unwrap_err()).contains("end of the file"));
+    }
+
+    #[test]
+    fn rejects_unknown_extensions() {
+        assert_eq!(
+            ProjectFormat::from_path(Path::new("a/b.RON")).unwrap(),
+            ProjectFormat::Ron
+        );
+        assert!(ProjectFormat::from_path(Path::new("project.yaml")).is_err());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod document;
+pub mod loader;
+
+pub use document::Project;
+pub use loader::ProjectFormat;
@@ -1,19 +1,70 @@
 use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
 
 use crate::scene::Vec2;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+// Penner easing families (formulas follow easings.net) plus stepped holds and
+// CSS `cubic-bezier()` curves. Back, elastic and bezier curves may overshoot 0..1.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
 pub enum Easing {
+    #[default]
     Linear,
+    EaseInSine,
+    EaseOutSine,
+    EaseInOutSine,
+    EaseInQuad,
+    EaseOutQuad,
     EaseInOutQuad,
+    EaseInCubic,
     EaseOutCubic,
+    EaseInOutCubic,
+    EaseInQuart,
+    EaseOutQuart,
+    EaseInOutQuart,
+    EaseInQuint,
+    EaseOutQuint,
+    EaseInOutQuint,
+    EaseInExpo,
+    EaseOutExpo,
+    EaseInOutExpo,
+    EaseInCirc,
+    EaseOutCirc,
+    EaseInOutCirc,
+    EaseInBack,
+    EaseOutBack,
+    EaseInOutBack,
+    EaseInElastic,
+    EaseOutElastic,
+    EaseInOutElastic,
+    EaseInBounce,
+    EaseOutBounce,
+    EaseInOutBounce,
+    // Holds the start value until the next keyframe, then jumps.
+    Hold,
+    // `n` equal jumps, like CSS `steps(n, jump-end)`.
+    Step(u32),
+    // Control points (x1, y1, x2, y2) of a CSS `cubic-bezier()`; x is clamped to 0..1.
+    CubicBezier(f32, f32, f32, f32),
 }
 
 impl Easing {
     pub fn apply(self, t: f32) -> f32 {
+        use std::f32::consts::PI;
+
+        const BACK_C1: f32 = 1.70158;
+        const BACK_C2: f32 = BACK_C1 * 1.525;
+        const BACK_C3: f32 = BACK_C1 + 1.0;
+        const ELASTIC_C4: f32 = 2.0 * PI / 3.0;
+        const ELASTIC_C5: f32 = 2.0 * PI / 4.5;
+
         let t = t.clamp(0.0, 1.0);
         match self {
             Easing::Linear => t,
+            Easing::EaseInSine => 1.0 - (t * PI / 2.0).cos(),
+            Easing::EaseOutSine => (t * PI / 2.0).sin(),
+            Easing::EaseInOutSine => -((PI * t).cos() - 1.0) / 2.0,
+            Easing::EaseInQuad => t * t,
+            Easing::EaseOutQuad => 1.0 - (1.0 - t).powi(2),
             Easing::EaseInOutQuad => {
                 if t < 0.5 {
                     2.0 * t * t
@@ -21,16 +72,212 @@ impl Easing {
                     1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
                 }
             }
+            Easing::EaseInCubic => t.powi(3),
             Easing::EaseOutCubic => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOutCubic => ease_in_out_pow(t, 3),
+            Easing::EaseInQuart => t.powi(4),
+            Easing::EaseOutQuart => 1.0 - (1.0 - t).powi(4),
+            Easing::EaseInOutQuart => ease_in_out_pow(t, 4),
+            Easing::EaseInQuint => t.powi(5),
+            Easing::EaseOutQuint => 1.0 - (1.0 - t).powi(5),
+            Easing::EaseInOutQuint => ease_in_out_pow(t, 5),
+            Easing::EaseInExpo => {
+                if t == 0.0 {
+                    0.0
+                } else {
+                    2f32.powf(10.0 * t - 10.0)
+                }
+            }
+            Easing::EaseOutExpo => {
+                if t == 1.0 {
+                    1.0
+                } else {
+                    1.0 - 2f32.powf(-10.0 * t)
+                }
+            }
+            Easing::EaseInOutExpo => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else if t < 0.5 {
+                    2f32.powf(20.0 * t - 10.0) / 2.0
+                } else {
+                    (2.0 - 2f32.powf(-20.0 * t + 10.0)) / 2.0
+                }
+            }
+            Easing::EaseInCirc => 1.0 - (1.0 - t * t).sqrt(),
+            Easing::EaseOutCirc => (1.0 - (t - 1.0).powi(2)).sqrt(),
+            Easing::EaseInOutCirc => {
+                if t < 0.5 {
+                    (1.0 - (1.0 - (2.0 * t).powi(2)).sqrt()) / 2.0
+                } else {
+                    ((1.0 - (-2.0 * t + 2.0).powi(2)).sqrt() + 1.0) / 2.0
+                }
+            }
+            Easing::EaseInBack => BACK_C3 * t.powi(3) - BACK_C1 * t * t,
+            Easing::EaseOutBack => {
+                1.0 + BACK_C3 * (t - 1.0).powi(3) + BACK_C1 * (t - 1.0).powi(2)
+            }
+            Easing::EaseInOutBack => {
+                if t < 0.5 {
+                    (2.0 * t).powi(2) * ((BACK_C2 + 1.0) * 2.0 * t - BACK_C2) / 2.0
+                } else {
+                    ((2.0 * t - 2.0).powi(2) * ((BACK_C2 + 1.0) * (t * 2.0 - 2.0) + BACK_C2)
+                        + 2.0)
+                        / 2.0
+                }
+            }
+            Easing::EaseInElastic => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    -(2f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * ELASTIC_C4).sin()
+                }
+            }
+            Easing::EaseOutElastic => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    2f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * ELASTIC_C4).sin() + 1.0
+                }
+            }
+            Easing::EaseInOutElastic => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else if t < 0.5 {
+                    -(2f32.powf(20.0 * t - 10.0) * ((20.0 * t - 11.125) * ELASTIC_C5).sin())
+                        / 2.0
+                } else {
+                    2f32.powf(-20.0 * t + 10.0) * ((20.0 * t - 11.125) * ELASTIC_C5).sin() / 2.0
+                        + 1.0
+                }
+            }
+            Easing::EaseInBounce => 1.0 - bounce_out(1.0 - t),
+            Easing::EaseOutBounce => bounce_out(t),
+            Easing::EaseInOutBounce => {
+                if t < 0.5 {
+                    (1.0 - bounce_out(1.0 - 2.0 * t)) / 2.0
+                } else {
+                    (1.0 + bounce_out(2.0 * t - 1.0)) / 2.0
+                }
+            }
+            Easing::Hold => {
+                if t >= 1.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Easing::Step(steps) => {
+                let steps = steps.max(1) as f32;
+                (t * steps).floor() / steps
+            }
+            Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier(t, x1, y1, x2, y2),
+        }
+    }
+}
+
+fn ease_in_out_pow(t: f32, n: i32) -> f32 {
+    if t < 0.5 {
+        2f32.powi(n - 1) * t.powi(n)
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(n) / 2.0
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N1: f32 = 7.5625;
+    const D1: f32 = 2.75;
+
+    if t < 1.0 / D1 {
+        N1 * t * t
+    } else if t < 2.0 / D1 {
+        let t = t - 1.5 / D1;
+        N1 * t * t + 0.75
+    } else if t < 2.5 / D1 {
+        let t = t - 2.25 / D1;
+        N1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D1;
+        N1 * t * t + 0.984375
+    }
+}
+
+// Solves x(s) = t for the curve parameter s, then returns y(s). Newton steps
+// converge quickly for typical curves; bisection covers flat spots.
+fn cubic_bezier(t: f32, x1: f32, y1: f32, x2: f32, y2: f32) -> f32 {
+    if t <= 0.0 || t >= 1.0 {
+        return t;
+    }
+    let x1 = x1.clamp(0.0, 1.0);
+    let x2 = x2.clamp(0.0, 1.0);
+
+    let bezier = |s: f32, p1: f32, p2: f32| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * s * p1 + 3.0 * inv * s * s * p2 + s * s * s
+    };
+    let slope = |s: f32, p1: f32, p2: f32| {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * p1 + 6.0 * inv * s * (p2 - p1) + 3.0 * s * s * (1.0 - p2)
+    };
+
+    let mut s = t;
+    for _ in 0..8 {
+        let err = bezier(s, x1, x2) - t;
+        if err.abs() < 1e-6 {
+            return bezier(s, y1, y2);
+        }
+        let d = slope(s, x1, x2);
+        if d.abs() < 1e-6 {
+            break;
+        }
+        s -= err / d;
+    }
+
+    let (mut lo, mut hi) = (0.0f32, 1.0f32);
+    s = t;
+    for _ in 0..32 {
+        let x = bezier(s, x1, x2);
+        if (x - t).abs() < 1e-6 {
+            break;
+        }
+        if x < t {
+            lo = s;
+        } else {
+            hi = s;
         }
+        s = (lo + hi) / 2.0;
     }
+    bezier(s, y1, y2)
 }
 
-#[derive(Debug, Clone, PartialEq)]
+// How a segment moves from its start keyframe to the next. Spline modes use
+// tangents in value units per second; a missing tangent falls back to the
+// Catmull-Rom slope through the neighbouring keyframes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Interpolation {
+    #[default]
+    Linear,
+    // Automatic tangents; explicit keyframe tangents are ignored.
+    CatmullRom,
+    // Cubic Hermite using `in_tangent` / `out_tangent` as slopes.
+    Hermite,
+    // Cubic Bezier where `out_tangent` / `in_tangent` are handle offsets from
+    // the keyframe value (the outgoing and incoming control points).
+    Bezier,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Keyframe<T> {
     pub time: f32,
     pub value: T,
+    #[serde(default)]
     pub easing_to_next: Easing,
+    #[serde(default)]
+    pub interpolation: Interpolation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_tangent: Option<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub out_tangent: Option<T>,
 }
 
 impl<T> Keyframe<T> {
@@ -39,18 +286,44 @@ impl<T> Keyframe<T> {
             time,
             value,
             easing_to_next,
+            interpolation: Interpolation::Linear,
+            in_tangent: None,
+            out_tangent: None,
         }
     }
+
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    pub fn with_tangents(mut self, in_tangent: Option<T>, out_tangent: Option<T>) -> Self {
+        self.in_tangent = in_tangent;
+        self.out_tangent = out_tangent;
+        self
+    }
 }
 
+// Values a track can animate.
 pub trait Lerp: Sized + Copy {
     fn lerp(a: Self, b: Self, t: f32) -> Self;
+
+    // `sum(weight * value)`, which spline segments and `velocity` need. Types
+    // that can only blend keep the default `None`: their spline segments fall
+    // back to `lerp` and `velocity` returns `None`.
+    fn weighted_sum(_terms: &[(Self, f32)]) -> Option<Self> {
+        None
+    }
 }
 
 impl Lerp for f32 {
     fn lerp(a: Self, b: Self, t: f32) -> Self {
         a + (b - a) * t
     }
+
+    fn weighted_sum(terms: &[(Self, f32)]) -> Option<Self> {
+        Some(terms.iter().map(|(value, weight)| value * weight).sum())
+    }
 }
 
 impl Lerp for Vec2 {
@@ -60,9 +333,21 @@ impl Lerp for Vec2 {
             y: a.y + (b.y - a.y) * t,
         }
     }
+
+    fn weighted_sum(terms: &[(Self, f32)]) -> Option<Self> {
+        Some(terms.iter().fold(Vec2::ZERO, |sum, (value, weight)| Vec2 {
+            x: sum.x + value.x * weight,
+            y: sum.y + value.y * weight,
+        }))
+    }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    try_from = "Vec<Keyframe<T>>",
+    into = "Vec<Keyframe<T>>",
+    bound(serialize = "T: Serialize + Clone", deserialize = "T: Lerp + Deserialize<'de>")
+)]
 pub struct Track<T> {
     keyframes: Vec<Keyframe<T>>,
 }
@@ -73,6 +358,9 @@ impl<T: Lerp> Track<T> {
             bail!("track must have at least one keyframe");
         }
 
+        if keyframes.iter().any(|k| !k.time.is_finite()) {
+            bail!("keyframe times must be finite");
+        }
         for i in 1..keyframes.len() {
             if keyframes[i].time <= keyframes[i - 1].time {
                 bail!("keyframe times must be strictly increasing");
@@ -88,30 +376,559 @@ impl<T: Lerp> Track<T> {
         }
     }
 
+    pub fn keyframes(&self) -> &[Keyframe<T>] {
+        &self.keyframes
+    }
+
+    // Shares keyframe lookup across calls; cheapest when times increase monotonically.
+    pub fn cursor(&self) -> TrackCursor<'_, T> {
+        TrackCursor {
+            track: self,
+            segment: 0,
+        }
+    }
+
     pub fn sample(&self, t: f32) -> T {
+        match self.clamp_sample(t) {
+            Some(value) => value,
+            None => self.sample_segment(self.segment_index(t), t),
+        }
+    }
+
+    // Rate of change in value units per second. Zero outside the keyframed
+    // range; `None` if `T` has no `weighted_sum`.
+    pub fn velocity(&self, t: f32) -> Option<T> {
+        if self.outside_velocity_range(t) {
+            return self.zero();
+        }
+        self.velocity_segment(self.segment_index(t), t)
+    }
+
+    fn clamp_sample(&self, t: f32) -> Option<T> {
         let first = &self.keyframes[0];
         let last = &self.keyframes[self.keyframes.len() - 1];
 
         if t <= first.time {
-            return first.value;
+            return Some(first.value);
         }
         if t >= last.time {
-            return last.value;
+            return Some(last.value);
         }
+        None
+    }
 
-        let mut idx = 0;
-        for i in 0..self.keyframes.len() - 1 {
-            if t >= self.keyframes[i].time && t < self.keyframes[i + 1].time {
-                idx = i;
-                break;
-            }
-        }
+    fn outside_velocity_range(&self, t: f32) -> bool {
+        let first = &self.keyframes[0];
+        let last = &self.keyframes[self.keyframes.len() - 1];
+        t < first.time || t >= last.time
+    }
+
+    fn zero(&self) -> Option<T> {
+        T::weighted_sum(&[(self.keyframes[0].value, 0.0)])
+    }
 
+    // Index of the keyframe starting the segment containing `t`; binary search.
+    // Callers guarantee `first.time <= t < last.time`.
+    fn segment_index(&self, t: f32) -> usize {
+        let upper = self.keyframes.partition_point(|k| k.time <= t);
+        upper.saturating_sub(1).min(self.keyframes.len() - 2)
+    }
+
+    fn sample_segment(&self, idx: usize, t: f32) -> T {
         let k0 = &self.keyframes[idx];
         let k1 = &self.keyframes[idx + 1];
         let span = k1.time - k0.time;
         let u = if span > 0.0 { (t - k0.time) / span } else { 0.0 };
         let eased = k0.easing_to_next.apply(u);
-        T::lerp(k0.value, k1.value, eased)
+
+        let spline = match k0.interpolation {
+            Interpolation::Linear => None,
+            _ => self.segment_tangents(idx).and_then(|(m0, m1)| {
+                let (h00, h10, h01, h11) = hermite_basis(eased);
+                T::weighted_sum(&[
+                    (k0.value, h00),
+                    (m0, h10 * span),
+                    (k1.value, h01),
+                    (m1, h11 * span),
+                ])
+            }),
+        };
+        spline.unwrap_or_else(|| T::lerp(k0.value, k1.value, eased))
+    }
+
+    fn velocity_segment(&self, idx: usize, t: f32) -> Option<T> {
+        let k0 = &self.keyframes[idx];
+        let k1 = &self.keyframes[idx + 1];
+        let span = k1.time - k0.time;
+        if span <= 0.0 {
+            return self.zero();
+        }
+        let u = (t - k0.time) / span;
+        let eased = k0.easing_to_next.apply(u);
+        // Chain rule through the easing curve, differentiated numerically.
+        let du = 1e-3;
+        let (a, b) = ((u - du).max(0.0), (u + du).min(1.0));
+        let ease_slope =
+            (k0.easing_to_next.apply(b) - k0.easing_to_next.apply(a)) / (b - a) / span;
+
+        match k0.interpolation {
+            Interpolation::Linear => {
+                T::weighted_sum(&[(k1.value, ease_slope), (k0.value, -ease_slope)])
+            }
+            _ => {
+                let (m0, m1) = self.segment_tangents(idx)?;
+                let (d00, d10, d01, d11) = hermite_basis_derivative(eased);
+                T::weighted_sum(&[
+                    (k0.value, d00 * ease_slope),
+                    (m0, d10 * span * ease_slope),
+                    (k1.value, d01 * ease_slope),
+                    (m1, d11 * span * ease_slope),
+                ])
+            }
+        }
+    }
+
+    // Start and end slopes (value units per second) for segment `idx`.
+    fn segment_tangents(&self, idx: usize) -> Option<(T, T)> {
+        let k0 = &self.keyframes[idx];
+        let k1 = &self.keyframes[idx + 1];
+        let span = k1.time - k0.time;
+
+        // Bezier handles are control-point offsets; the curve leaves its end
+        // points with slope 3 * handle / span.
+        let slope_from_handle = |handle: Option<T>, scale: f32| match handle {
+            Some(handle) => T::weighted_sum(&[(handle, scale)]).map(Some),
+            None => Some(None),
+        };
+        let (out_tangent, in_tangent) = match k0.interpolation {
+            Interpolation::Linear | Interpolation::CatmullRom => (None, None),
+            Interpolation::Hermite => (k0.out_tangent, k1.in_tangent),
+            Interpolation::Bezier => (
+                slope_from_handle(k0.out_tangent, 3.0 / span)?,
+                slope_from_handle(k1.in_tangent, -3.0 / span)?,
+            ),
+        };
+        let m0 = match out_tangent {
+            Some(tangent) => tangent,
+            None => self.auto_tangent(idx)?,
+        };
+        let m1 = match in_tangent {
+            Some(tangent) => tangent,
+            None => self.auto_tangent(idx + 1)?,
+        };
+        Some((m0, m1))
+    }
+
+    // Catmull-Rom slope for non-uniform keyframe spacing; one-sided at the ends.
+    fn auto_tangent(&self, idx: usize) -> Option<T> {
+        let keys = &self.keyframes;
+        let prev = &keys[idx.saturating_sub(1)];
+        let next = &keys[(idx + 1).min(keys.len() - 1)];
+        let dt = next.time - prev.time;
+        if dt <= 0.0 {
+            return T::weighted_sum(&[(keys[idx].value, 0.0)]);
+        }
+        T::weighted_sum(&[(next.value, 1.0 / dt), (prev.value, -1.0 / dt)])
+    }
+}
+
+// Sequential sampler for render loops. Remembers the last segment and walks
+// forward from it, so sampling every frame in order is amortized O(1). Seeking
+// backwards falls back to a binary search.
+pub struct TrackCursor<'a, T> {
+    track: &'a Track<T>,
+    segment: usize,
+}
+
+impl<T: Lerp> TrackCursor<'_, T> {
+    pub fn sample(&mut self, t: f32) -> T {
+        if let Some(value) = self.track.clamp_sample(t) {
+            return value;
+        }
+        let idx = self.seek(t);
+        self.track.sample_segment(idx, t)
+    }
+
+    pub fn velocity(&mut self, t: f32) -> Option<T> {
+        if self.track.outside_velocity_range(t) {
+            return self.track.zero();
+        }
+        let idx = self.seek(t);
+        self.track.velocity_segment(idx, t)
+    }
+
+    fn seek(&mut self, t: f32) -> usize {
+        let keys = &self.track.keyframes;
+        let mut idx = self.segment.min(keys.len() - 2);
+        if t < keys[idx].time {
+            idx = self.track.segment_index(t);
+        } else {
+            // A few forward steps cover normal playback; larger jumps search.
+            let mut steps = 0;
+            while t >= keys[idx + 1].time {
+                idx += 1;
+                steps += 1;
+                if steps == 8 {
+                    idx = self.track.segment_index(t);
+                    break;
+                }
+            }
+        }
+        self.segment = idx;
+        idx
+    }
+}
+
+fn hermite_basis(u: f32) -> (f32, f32, f32, f32) {
+    let u2 = u * u;
+    let u3 = u2 * u;
+    (
+        2.0 * u3 - 3.0 * u2 + 1.0,
+        u3 - 2.0 * u2 + u,
+        -2.0 * u3 + 3.0 * u2,
+        u3 - u2,
+    )
+}
+
+fn hermite_basis_derivative(u: f32) -> (f32, f32, f32, f32) {
+    let u2 = u * u;
+    (
+        6.0 * u2 - 6.0 * u,
+        3.0 * u2 - 4.0 * u + 1.0,
+        -6.0 * u2 + 6.0 * u,
+        3.0 * u2 - 2.0 * u,
+    )
+}
+
+impl<T: Lerp> TryFrom<Vec<Keyframe<T>>> for Track<T> {
+    type Error = anyhow::Error;
+
+    fn try_from(keyframes: Vec<Keyframe<T>>) -> Result<Self> {
+        Track::new(keyframes)
+    }
+}
+
+impl<T> From<Track<T>> for Vec<Keyframe<T>> {
+    fn from(track: Track<T>) -> Self {
+        track.keyframes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PENNER: [Easing; 31] = [
+        Easing::Linear,
+        Easing::EaseInSine,
+        Easing::EaseOutSine,
+        Easing::EaseInOutSine,
+        Easing::EaseInQuad,
+        Easing::EaseOutQuad,
+        Easing::EaseInOutQuad,
+        Easing::EaseInCubic,
+        Easing::EaseOutCubic,
+        Easing::EaseInOutCubic,
+        Easing::EaseInQuart,
+        Easing::EaseOutQuart,
+        Easing::EaseInOutQuart,
+        Easing::EaseInQuint,
+        Easing::EaseOutQuint,
+        Easing::EaseInOutQuint,
+        Easing::EaseInExpo,
+        Easing::EaseOutExpo,
+        Easing::EaseInOutExpo,
+        Easing::EaseInCirc,
+        Easing::EaseOutCirc,
+        Easing::EaseInOutCirc,
+        Easing::EaseInBack,
+        Easing::EaseOutBack,
+        Easing::EaseInOutBack,
+        Easing::EaseInElastic,
+        Easing::EaseOutElastic,
+        Easing::EaseInOutElastic,
+        Easing::EaseInBounce,
+        Easing::EaseOutBounce,
+        Easing::EaseInOutBounce,
+    ];
+
+    fn close(a: f32, b: f32, eps: f32) -> bool {
+        (a - b).abs() <= eps
+    }
+
+    #[test]
+    fn penner_easings_hit_both_endpoints() {
+        for easing in PENNER {
+            assert!(close(easing.apply(0.0), 0.0, 1e-5), "{easing:?} at 0");
+            assert!(close(easing.apply(1.0), 1.0, 1e-5), "{easing:?} at 1");
+            // Input is clamped to 0..1.
+            assert_eq!(easing.apply(-1.0), easing.apply(0.0), "{easing:?}");
+            assert_eq!(easing.apply(2.0), easing.apply(1.0), "{easing:?}");
+        }
+        // In-out curves are symmetric about the midpoint.
+        for easing in [Easing::EaseInOutSine, Easing::EaseInOutCubic, Easing::EaseInOutBounce] {
+            assert!(close(easing.apply(0.5), 0.5, 1e-5), "{easing:?}");
+        }
+        assert!(Easing::EaseInBack.apply(0.2) < 0.0);
+        assert!(Easing::EaseOutBack.apply(0.8) > 1.0);
+    }
+
+    #[test]
+    fn steps_and_holds_jump() {
+        let steps = Easing::Step(4);
+        assert_eq!(steps.apply(0.0), 0.0);
+        assert_eq!(steps.apply(0.24), 0.0);
+        assert_eq!(steps.apply(0.25), 0.25);
+        assert_eq!(steps.apply(0.6), 0.5);
+        assert_eq!(steps.apply(0.99), 0.75);
+        assert_eq!(steps.apply(1.0), 1.0);
+        // Zero steps behaves like one.
+        assert_eq!(Easing::Step(0).apply(0.5), 0.0);
+
+        assert_eq!(Easing::Hold.apply(0.0), 0.0);
+        assert_eq!(Easing::Hold.apply(0.999), 0.0);
+        assert_eq!(Easing::Hold.apply(1.0), 1.0);
+
+        let track = Track::new(vec![
+            Keyframe::new(0.0, 2.0, Easing::Hold),
+            Keyframe::new(1.0, 5.0, Easing::Linear),
+        ])
+        .unwrap();
+        assert_eq!(track.sample(0.9), 2.0);
+        assert_eq!(track.sample(1.0), 5.0);
+    }
+
+    #[test]
+    fn cubic_bezier_matches_css_timing_functions() {
+        // Reference values from the CSS `cubic-bezier()` definition.
+        let ease = Easing::CubicBezier(0.25, 0.1, 0.25, 1.0);
+        assert!(close(ease.apply(0.5), 0.8024, 1e-3), "{}", ease.apply(0.5));
+        assert!(close(ease.apply(0.25), 0.4094, 1e-3), "{}", ease.apply(0.25));
+        let ease_in = Easing::CubicBezier(0.42, 0.0, 1.0, 1.0);
+        assert!(close(ease_in.apply(0.5), 0.3153, 1e-3), "{}", ease_in.apply(0.5));
+        let ease_out = Easing::CubicBezier(0.0, 0.0, 0.58, 1.0);
+        assert!(close(ease_out.apply(0.5), 0.6847, 1e-3), "{}", ease_out.apply(0.5));
+        let ease_in_out = Easing::CubicBezier(0.42, 0.0, 0.58, 1.0);
+        assert!(close(ease_in_out.apply(0.5), 0.5, 1e-4));
+
+        let linear = Easing::CubicBezier(0.0, 0.0, 1.0, 1.0);
+        for t in [0.1, 0.3, 0.7, 0.9] {
+            assert!(close(linear.apply(t), t, 1e-4), "{t}");
+        }
+        assert_eq!(ease.apply(0.0), 0.0);
+        assert_eq!(ease.apply(1.0), 1.0);
+        // y may overshoot; x outside 0..1 is clamped.
+        let overshoot = Easing::CubicBezier(0.3, 1.5, 0.7, 1.5);
+        assert!(overshoot.apply(0.5) > 1.0);
+        let clamped = Easing::CubicBezier(-1.0, 0.0, 2.0, 1.0);
+        assert_eq!(clamped.apply(0.3), Easing::CubicBezier(0.0, 0.0, 1.0, 1.0).apply(0.3));
+    }
+
+    fn spline(interpolation: Interpolation) -> Track<Vec2> {
+        let key = |time: f32, x: f32, y: f32| {
+            Keyframe::new(time, Vec2 { x, y }, Easing::Linear).with_interpolation(interpolation)
+        };
+        Track::new(vec![
+            key(0.0, 0.0, 0.0),
+            key(1.0, 10.0, 5.0),
+            key(3.0, 20.0, -5.0),
+            key(4.0, 0.0, 0.0),
+        ])
+        .unwrap()
+    }
+
+    fn assert_vec_close(a: Vec2, b: Vec2, eps: f32) {
+        assert!(close(a.x, b.x, eps) && close(a.y, b.y, eps), "{a:?} != {b:?}");
+    }
+
+    fn splines() -> [Track<Vec2>; 3] {
+        let hermite = Track::new(
+            spline(Interpolation::Hermite)
+                .keyframes()
+                .iter()
+                .cloned()
+                .map(|k| {
+                    k.with_tangents(Some(Vec2 { x: 4.0, y: -2.0 }), Some(Vec2 { x: 4.0, y: -2.0 }))
+                })
+                .collect(),
+        )
+        .unwrap();
+        let bezier = Track::new(
+            spline(Interpolation::Bezier)
+                .keyframes()
+                .iter()
+                .cloned()
+                .map(|k| {
+                    k.with_tangents(Some(Vec2 { x: -1.0, y: 0.5 }), Some(Vec2 { x: 1.0, y: -0.5 }))
+                })
+                .collect(),
+        )
+        .unwrap();
+        [spline(Interpolation::CatmullRom), hermite, bezier]
+    }
+
+    #[test]
+    fn splines_pass_through_their_keyframes() {
+        for track in splines() {
+            for key in track.keyframes() {
+                assert_vec_close(track.sample(key.time), key.value, 1e-4);
+                // Approaching the key from inside the previous segment too.
+                assert_vec_close(track.sample(key.time - 1e-4), key.value, 1e-2);
+            }
+            // And actually curve between them.
+            let linear = Vec2::lerp(track.keyframes()[1].value, track.keyframes()[2].value, 0.25);
+            let curved = track.sample(1.5);
+            assert!((curved.x - linear.x).abs() > 0.1 || (curved.y - linear.y).abs() > 0.1);
+        }
+    }
+
+    #[test]
+    fn spline_tangents_are_continuous_across_keys() {
+        // Explicit Bezier handles may add a corner on purpose; automatic ones don't.
+        let [catmull_rom, hermite, _] = splines();
+        for track in [catmull_rom, hermite, spline(Interpolation::Bezier)] {
+            for t in [1.0, 3.0] {
+                let before = track.velocity(t - 1e-4).unwrap();
+                let after = track.velocity(t).unwrap();
+                assert_vec_close(before, after, 0.02);
+            }
+        }
+        // Explicit tangents are the slopes at the key.
+        let [_, hermite, bezier] = splines();
+        assert_vec_close(hermite.velocity(1.0).unwrap(), Vec2 { x: 4.0, y: -2.0 }, 1e-3);
+        // Handle (-1, 0.5) on a 2 s incoming segment: slope = -3 * handle / 2.
+        let before = bezier.velocity(1.0 - 1e-4).unwrap();
+        assert_vec_close(before, Vec2 { x: 3.0, y: -1.5 }, 1e-2);
+    }
+
+    #[test]
+    fn velocity_matches_finite_differences() {
+        let h = 1e-3;
+        for track in splines() {
+            for t in [0.2, 0.9, 1.7, 2.5, 3.6] {
+                let expected = Vec2 {
+                    x: (track.sample(t + h).x - track.sample(t - h).x) / (2.0 * h),
+                    y: (track.sample(t + h).y - track.sample(t - h).y) / (2.0 * h),
+                };
+                assert_vec_close(track.velocity(t).unwrap(), expected, 0.05);
+            }
+        }
+
+        // Easing is included through the chain rule.
+        let eased = Track::new(vec![
+            Keyframe::new(0.0, 0.0, Easing::EaseInOutCubic)
+                .with_interpolation(Interpolation::CatmullRom),
+            Keyframe::new(2.0, 10.0, Easing::Linear),
+        ])
+        .unwrap();
+        for t in [0.3, 1.0, 1.6] {
+            let expected = (eased.sample(t + h) - eased.sample(t - h)) / (2.0 * h);
+            assert!(close(eased.velocity(t).unwrap(), expected, 0.05), "{t}");
+        }
+        let mut cursor = eased.cursor();
+        assert_eq!(cursor.velocity(1.0), eased.velocity(1.0));
+        // Zero outside the keyframed range.
+        assert_eq!(eased.velocity(-1.0), Some(0.0));
+        assert_eq!(eased.velocity(2.0), Some(0.0));
+    }
+
+    // A value type that only implements the required `lerp`.
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Level(f32);
+
+    impl Lerp for Level {
+        fn lerp(a: Self, b: Self, t: f32) -> Self {
+            Level(a.0 + (b.0 - a.0) * t)
+        }
+    }
+
+    #[test]
+    fn blend_only_values_fall_back_to_linear_segments() {
+        let track = Track::new(vec![
+            Keyframe::new(0.0, Level(0.0), Easing::Linear)
+                .with_interpolation(Interpolation::CatmullRom),
+            Keyframe::new(1.0, Level(1.0), Easing::Linear)
+                .with_interpolation(Interpolation::CatmullRom),
+            Keyframe::new(2.0, Level(4.0), Easing::Linear),
+        ])
+        .unwrap();
+        assert_eq!(track.sample(0.5), Level(0.5));
+        assert_eq!(track.sample(1.5), Level(2.5));
+        assert_eq!(track.velocity(0.5), None);
+    }
+
+    // 40 one-second segments with uneven values and easings, so sampling the
+    // wrong segment shows up as a different value.
+    fn long_track() -> Track<f32> {
+        let easings = [
+            Easing::Linear,
+            Easing::EaseInQuad,
+            Easing::EaseOutCubic,
+            Easing::EaseInOutSine,
+        ];
+        Track::new(
+            (0..40)
+                .map(|i| Keyframe::new(i as f32, ((i * i) % 7) as f32, easings[i % easings.len()]))
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn cursor_seeks_backward_and_jumps_forward() {
+        let track = long_track();
+        let mut cursor = track.cursor();
+        for t in [0.5, 1.5, 2.5, 3.5, 4.5, 5.5] {
+            assert_eq!(cursor.sample(t), track.sample(t));
+        }
+        assert_eq!(cursor.segment, 5);
+
+        // Backwards: binary search.
+        assert_eq!(cursor.sample(2.25), track.sample(2.25));
+        assert_eq!(cursor.segment, 2);
+
+        // More than 8 segments ahead: the forward walk gives up and searches.
+        assert_eq!(cursor.sample(30.5), track.sample(30.5));
+        assert_eq!(cursor.segment, 30);
+        // Exactly 8 ahead hits the limit on the last step.
+        assert_eq!(cursor.sample(38.5), track.sample(38.5));
+        assert_eq!(cursor.segment, 38);
+        assert_eq!(cursor.velocity(38.5), track.velocity(38.5));
+    }
+
+    #[test]
+    fn cursor_clamps_and_lands_on_keys() {
+        let track = long_track();
+        let (first, last) = (track.sample(0.0), track.sample(39.0));
+        let mut cursor = track.cursor();
+        assert_eq!(cursor.sample(-1.0), first);
+        assert_eq!(cursor.sample(0.0), first);
+        assert_eq!(cursor.sample(39.0), last);
+        assert_eq!(cursor.sample(50.0), last);
+
+        // A key starts the segment after it, as in `segment_index`.
+        for key in [7.0, 8.0, 3.0, 12.0] {
+            assert_eq!(cursor.sample(key), track.keyframes()[key as usize].value);
+            assert_eq!(cursor.segment, key as usize);
+        }
+        // The last segment ends on the last key without running past it.
+        assert_eq!(cursor.sample(38.999), track.sample(38.999));
+        assert_eq!(cursor.segment, 38);
+    }
+
+    #[test]
+    fn cursor_agrees_with_sample_in_any_order() {
+        let track = long_track();
+        let mut cursor = track.cursor();
+        // Deterministic LCG shuffle over -2..42 s, mixing small steps and jumps.
+        let mut state = 0x2545_f491_u32;
+        for _ in 0..2000 {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            let t = (state >> 8) as f32 / (1u32 << 24) as f32 * 44.0 - 2.0;
+            assert_eq!(cursor.sample(t), track.sample(t), "t = {t}");
+            if (0.0..39.0).contains(&t) {
+                assert_eq!(cursor.segment, track.segment_index(t), "t = {t}");
+            }
+        }
     }
 }
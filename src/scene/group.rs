@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+use crate::timeline::Clip;
+
+// Clips that move together. Child start/end times are relative to the start of
+// the clip holding the group, and each child's transform is composed with the
+// group's sampled transform in `Timeline::sample`.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Group {
+    #[serde(default)]
+    pub children: Vec<Clip>,
+}
+
+impl Group {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_child(mut self, clip: Clip) -> Self {
+        self.children.push(clip);
+        self
+    }
+
+    pub fn add_child(&mut self, clip: Clip) {
+        self.children.push(clip);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod group;
 pub mod image;
 pub mod object;
 pub mod shape;
@@ -5,9 +6,14 @@ pub mod transform;
 pub mod animation;
 pub mod text;
 
+pub use group::Group;
 pub use image::ImageObject;
 pub use object::Object;
 pub use shape::Shape;
-pub use transform::{AnimatedTransform, Color, Transform, Vec2};
-pub use animation::{Easing, Keyframe, Track};
-pub use text::{FontFamily, FontSource, StyleFlags, StyledText, TextObject, TextRun};
+pub use transform::{
+    AnimatedAnchor, AnimatedTransform, Anchor, AnchorMode, Color, Transform, Vec2,
+};
+pub use animation::{Easing, Interpolation, Keyframe, Lerp, Track, TrackCursor};
+pub use text::{
+    FontFamily, FontSource, StyleFlags, StyledText, TextAlign, TextObject, TextRun, VerticalAlign,
+};
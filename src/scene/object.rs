@@ -1,8 +1,11 @@
-use crate::scene::{ImageObject, Shape, TextObject};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq)]
+use crate::scene::{Group, ImageObject, Shape, TextObject};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Object {
     Shape(Shape),
     Image(ImageObject),
     Text(TextObject),
+    Group(Group),
 }
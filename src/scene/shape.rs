@@ -1,6 +1,8 @@
+use serde::{Deserialize, Serialize};
+
 use crate::scene::Color;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Shape {
     Circle { radius: f32, color: Color },
     Rect { width: f32, height: f32, color: Color },
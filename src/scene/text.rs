@@ -1,12 +1,21 @@
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StyleFlags {
     pub bold: bool,
     pub italic: bool,
     pub underline: bool,
 }
 
+impl Default for StyleFlags {
+    fn default() -> Self {
+        StyleFlags::PLAIN
+    }
+}
+
 impl StyleFlags {
     pub const PLAIN: StyleFlags = StyleFlags {
         bold: false,
@@ -15,13 +24,14 @@ impl StyleFlags {
     };
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextRun {
     pub text: String,
     pub style: StyleFlags,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "StyledTextRepr")]
 pub struct StyledText {
     pub runs: Vec<TextRun>,
 }
@@ -76,6 +86,23 @@ impl StyledText {
     }
 }
 
+// Documents may give styled text as a markdown string or as explicit runs.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StyledTextRepr {
+    Markdown(String),
+    Runs { runs: Vec<TextRun> },
+}
+
+impl From<StyledTextRepr> for StyledText {
+    fn from(repr: StyledTextRepr) -> Self {
+        match repr {
+            StyledTextRepr::Markdown(input) => StyledText::from_markdown(&input),
+            StyledTextRepr::Runs { runs } => StyledText { runs },
+        }
+    }
+}
+
 fn flush_run(runs: &mut Vec<TextRun>, buffer: &mut String, style: StyleFlags) {
     if !buffer.is_empty() {
         runs.push(TextRun {
@@ -86,13 +113,13 @@ fn flush_run(runs: &mut Vec<TextRun>, buffer: &mut String, style: StyleFlags) {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum FontSource {
     Default,
     Path(PathBuf),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FontFamily {
     pub regular: FontSource,
     pub bold: Option<FontSource>,
@@ -130,7 +157,30 @@ impl FontFamily {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+// Horizontal placement of each line within the block, which is as wide as its
+// widest line. `Justify` stretches word gaps on every line except the last line
+// of a paragraph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+    Justify,
+}
+
+// Which part of the block sits at `Transform::pos` when the transform has no
+// explicit anchor. `Baseline` is the first line's baseline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum VerticalAlign {
+    #[default]
+    Top,
+    Middle,
+    Baseline,
+    Bottom,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextObject {
     pub text: StyledText,
     pub font: FontFamily,
@@ -139,4 +189,8 @@ pub struct TextObject {
     pub max_width: f32,
     pub color: crate::scene::Color,
     pub line_spacing: f32,
+    #[serde(default)]
+    pub align: TextAlign,
+    #[serde(default)]
+    pub vertical_align: VerticalAlign,
 }
@@ -1,6 +1,8 @@
+use serde::{Deserialize, Serialize};
+
 use crate::scene::animation::Track;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Vec2 {
     pub x: f32,
     pub y: f32,
@@ -11,11 +13,12 @@ impl Vec2 {
     pub const ONE: Vec2 = Vec2 { x: 1.0, y: 1.0 };
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
     pub b: u8,
+    #[serde(default = "opaque")]
     pub a: u8,
 }
 
@@ -32,6 +35,13 @@ impl Color {
         b: 0,
         a: 255,
     };
+    // Clear color for renders that keep their alpha channel.
+    pub const TRANSPARENT: Color = Color {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 0,
+    };
 
     pub fn rgb(r: u8, g: u8, b: u8) -> Self {
         Self { r, g, b, a: 255 }
@@ -48,12 +58,102 @@ impl Color {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+fn opaque() -> u8 {
+    255
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AnchorMode {
+    // Fraction of the object's bounds: (0, 0) is the top-left corner,
+    // (0.5, 0.5) the center and (1, 1) the bottom-right corner.
+    #[default]
+    Normalized,
+    // Graph units (+Y up) measured from the center of the unscaled bounds.
+    Units,
+}
+
+// The point of an object that sits at `Transform::pos` and that rotation and
+// scale pivot around.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Anchor {
+    pub point: Vec2,
+    #[serde(default)]
+    pub mode: AnchorMode,
+}
+
+impl Anchor {
+    pub const TOP_LEFT: Anchor = Anchor::normalized(0.0, 0.0);
+    pub const CENTER: Anchor = Anchor::normalized(0.5, 0.5);
+
+    pub const fn normalized(x: f32, y: f32) -> Self {
+        Self {
+            point: Vec2 { x, y },
+            mode: AnchorMode::Normalized,
+        }
+    }
+
+    pub const fn units(x: f32, y: f32) -> Self {
+        Self {
+            point: Vec2 { x, y },
+            mode: AnchorMode::Units,
+        }
+    }
+
+    // Offset of the anchor from the top-left of a `width` x `height` box, in
+    // screen orientation (+y down) and unscaled units.
+    pub fn offset(&self, width: f32, height: f32) -> Vec2 {
+        match self.mode {
+            AnchorMode::Normalized => Vec2 {
+                x: self.point.x * width,
+                y: self.point.y * height,
+            },
+            AnchorMode::Units => Vec2 {
+                x: width / 2.0 + self.point.x,
+                y: height / 2.0 - self.point.y,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Transform {
     pub pos: Vec2,
     pub scale: Vec2,
     pub rotation: f32,
     pub opacity: f32,
+    // `None` keeps each object's default pivot: the center for shapes and
+    // images, the top-left corner for text blocks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<Anchor>,
+}
+
+impl Transform {
+    // Places `child` (expressed in this transform's local space) into the
+    // parent's space: offset scaled and rotated by the parent, scales and
+    // opacities multiplied, rotations added. Rotation is clockwise on screen,
+    // matching the backends.
+    pub fn compose(&self, child: &Transform) -> Transform {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        let x = child.pos.x * self.scale.x;
+        let y = child.pos.y * self.scale.y;
+        Transform {
+            pos: Vec2 {
+                x: self.pos.x + x * cos + y * sin,
+                y: self.pos.y - x * sin + y * cos,
+            },
+            scale: Vec2 {
+                x: self.scale.x * child.scale.x,
+                y: self.scale.y * child.scale.y,
+            },
+            rotation: self.rotation + child.rotation,
+            opacity: self.opacity * child.opacity,
+            anchor: child.anchor,
+        }
+    }
+
+    pub fn anchor_or(&self, default: Anchor) -> Anchor {
+        self.anchor.unwrap_or(default)
+    }
 }
 
 impl Default for Transform {
@@ -63,16 +163,43 @@ impl Default for Transform {
             scale: Vec2::ONE,
             rotation: 0.0,
             opacity: 1.0,
+            anchor: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimatedAnchor {
+    pub point: Track<Vec2>,
+    #[serde(default)]
+    pub mode: AnchorMode,
+}
+
+impl AnimatedAnchor {
+    pub fn constant(anchor: Anchor) -> Self {
+        Self {
+            point: Track::from_constant(anchor.point),
+            mode: anchor.mode,
+        }
+    }
+
+    pub fn sample(&self, t: f32) -> Anchor {
+        Anchor {
+            point: self.point.sample(t),
+            mode: self.mode,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AnimatedTransform {
     pub position: Track<Vec2>,
     pub scale: Track<Vec2>,
     pub rotation: Track<f32>,
     pub opacity: Track<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<AnimatedAnchor>,
 }
 
 impl AnimatedTransform {
@@ -82,6 +209,7 @@ impl AnimatedTransform {
             scale: Track::from_constant(transform.scale),
             rotation: Track::from_constant(transform.rotation),
             opacity: Track::from_constant(transform.opacity),
+            anchor: transform.anchor.map(AnimatedAnchor::constant),
         }
     }
 
@@ -91,6 +219,7 @@ impl AnimatedTransform {
             scale: self.scale.sample(t),
             rotation: self.rotation.sample(t),
             opacity: self.opacity.sample(t),
+            anchor: self.anchor.as_ref().map(|anchor| anchor.sample(t)),
         }
     }
 }
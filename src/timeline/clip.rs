@@ -1,12 +1,14 @@
 use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
 
 use crate::scene::{AnimatedTransform, Object};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Clip {
     pub start: f32,
     pub end: f32,
     pub object: Object,
+    #[serde(default)]
     pub transform: AnimatedTransform,
 }
 
@@ -1,9 +1,13 @@
+use serde::{Deserialize, Serialize};
+
 use crate::timeline::Clip;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Layer {
     pub name: String,
+    #[serde(default)]
     pub z_override: Option<i32>,
+    #[serde(default)]
     pub clips: Vec<Clip>,
 }
 
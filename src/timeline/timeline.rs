@@ -1,11 +1,14 @@
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
 
+use crate::scene::{Object, Transform};
 use crate::timeline::{Clip, Layer};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Timeline {
     pub duration: f32,
     pub fps: u32,
+    #[serde(default)]
     pub layers: Vec<Layer>,
 }
 
@@ -61,13 +64,7 @@ impl Timeline {
         for (_, layer) in ordered {
             let mut clips = Vec::new();
             for clip in &layer.clips {
-                if clip.is_active(t) {
-                    let local_t = clip.clamped_local_time(t);
-                    clips.push(SampledClip {
-                        object: clip.object.clone(),
-                        transform: clip.transform.sample(local_t),
-                    });
-                }
+                sample_clip(clip, t, &Transform::default(), &mut clips);
             }
             sampled_layers.push(SampledLayer {
                 name: layer.name.clone(),
@@ -85,6 +82,27 @@ impl Timeline {
     }
 }
 
+// Groups are flattened here, so sampled clips only hold drawable objects with
+// world-space transforms.
+fn sample_clip(clip: &Clip, t: f32, parent: &Transform, out: &mut Vec<SampledClip>) {
+    if !clip.is_active(t) {
+        return;
+    }
+    let local_t = clip.clamped_local_time(t);
+    let transform = parent.compose(&clip.transform.sample(local_t));
+    match &clip.object {
+        Object::Group(group) => {
+            for child in &group.children {
+                sample_clip(child, local_t, &transform, out);
+            }
+        }
+        object => out.push(SampledClip {
+            object: object.clone(),
+            transform,
+        }),
+    }
+}
+
 impl Clip {
     pub fn validate_against(&self, duration: f32) -> Result<()> {
         if duration <= 0.0 {
@@ -93,6 +111,121 @@ impl Clip {
         if self.start < 0.0 || self.end <= self.start || self.end > duration {
             bail!("clip bounds must satisfy 0 <= start < end <= duration");
         }
+        if let Object::Group(group) = &self.object {
+            let length = self.end - self.start;
+            for (idx, child) in group.children.iter().enumerate() {
+                child
+                    .validate_against(length)
+                    .with_context(|| format!("group child {idx}"))?;
+            }
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{AnimatedTransform, Color, Group, Shape, Vec2};
+
+    fn dot() -> Object {
+        Object::Shape(Shape::Circle {
+            radius: 1.0,
+            color: Color::WHITE,
+        })
+    }
+
+    fn child(start: f32, end: f32, length: f32, transform: Transform) -> Clip {
+        Clip::new(start, end, dot(), AnimatedTransform::constant(transform), length).unwrap()
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn groups_compose_parent_transforms() {
+        let group_transform = Transform {
+            pos: Vec2 { x: 100.0, y: 50.0 },
+            scale: Vec2 { x: 2.0, y: 3.0 },
+            rotation: 90.0,
+            opacity: 0.5,
+            anchor: None,
+        };
+        let right = Transform {
+            pos: Vec2 { x: 10.0, y: 0.0 },
+            scale: Vec2 { x: 0.5, y: 0.5 },
+            rotation: 15.0,
+            opacity: 0.8,
+            anchor: None,
+        };
+        let up = Transform {
+            pos: Vec2 { x: 0.0, y: 10.0 },
+            ..Transform::default()
+        };
+        let group = Group::new()
+            .with_child(child(0.0, 2.0, 2.0, right))
+            .with_child(child(1.0, 2.0, 2.0, up));
+
+        let mut timeline = Timeline::new(5.0, 30).unwrap();
+        let mut layer = Layer::new("group");
+        let transform = AnimatedTransform::constant(group_transform);
+        layer.add_clip(Clip::new(1.0, 3.0, Object::Group(group), transform, 5.0).unwrap());
+        timeline.add_layer(layer);
+
+        // Only the first child is active 0.5 s into the group.
+        let scene = timeline.sample(1.5).unwrap();
+        assert_eq!(scene.layers[0].clips.len(), 1);
+        let sampled = scene.layers[0].clips[0].transform;
+        // Offset scaled by the parent, then turned 90 degrees clockwise on
+        // screen: +x in the group points down (-y) in the world.
+        assert!(close(sampled.pos.x, 100.0), "{:?}", sampled.pos);
+        assert!(close(sampled.pos.y, 30.0), "{:?}", sampled.pos);
+        assert_eq!(sampled.scale, Vec2 { x: 1.0, y: 1.5 });
+        assert_eq!(sampled.rotation, 105.0);
+        assert!(close(sampled.opacity, 0.4));
+
+        let scene = timeline.sample(2.5).unwrap();
+        let clips = &scene.layers[0].clips;
+        assert_eq!(clips.len(), 2);
+        assert!(close(clips[1].transform.pos.x, 130.0), "{:?}", clips[1].transform.pos);
+        assert!(close(clips[1].transform.pos.y, 50.0), "{:?}", clips[1].transform.pos);
+        assert_eq!(clips[1].transform.opacity, 0.5);
+
+        assert!(timeline.sample(3.5).unwrap().layers[0].clips.is_empty());
+    }
+
+    #[test]
+    fn nested_groups_compose_in_order() {
+        let shift = |x: f32| Transform {
+            pos: Vec2 { x, y: 0.0 },
+            ..Transform::default()
+        };
+        let inner = Group::new().with_child(child(0.0, 1.0, 1.0, shift(1.0)));
+        let inner = Clip::new(
+            0.0,
+            1.0,
+            Object::Group(inner),
+            AnimatedTransform::constant(Transform {
+                scale: Vec2 { x: 4.0, y: 4.0 },
+                ..shift(10.0)
+            }),
+            1.0,
+        )
+        .unwrap();
+        let outer = Group::new().with_child(inner);
+        let transform = AnimatedTransform::constant(Transform {
+            rotation: 180.0,
+            ..shift(100.0)
+        });
+
+        let mut timeline = Timeline::new(1.0, 30).unwrap();
+        let mut layer = Layer::new("nested");
+        layer.add_clip(Clip::new(0.0, 1.0, Object::Group(outer), transform, 1.0).unwrap());
+        timeline.add_layer(layer);
+
+        let pos = timeline.sample(0.5).unwrap().layers[0].clips[0].transform.pos;
+        // 100 - (10 + 1 * 4) once the outer half-turn flips the x axis.
+        assert!(close(pos.x, 86.0) && close(pos.y, 0.0), "{pos:?}");
+    }
+}
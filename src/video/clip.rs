@@ -1,13 +1,16 @@
 use std::path::PathBuf;
 
 use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VideoClip {
     pub path: PathBuf,
     pub start_time: f32,
     pub end_time: f32,
+    #[serde(default)]
     pub trim_start: Option<f32>,
+    #[serde(default)]
     pub trim_end: Option<f32>,
 }
 
@@ -19,40 +22,43 @@ impl VideoClip {
         trim_start: Option<f32>,
         trim_end: Option<f32>,
     ) -> Result<Self> {
-        let path = path.into();
-        if !path.exists() {
-            bail!("video clip not found: {}", path.display());
+        let clip = Self {
+            path: path.into(),
+            start_time,
+            end_time,
+            trim_start,
+            trim_end,
+        };
+        clip.validate()?;
+        Ok(clip)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if !self.path.exists() {
+            bail!("video clip not found: {}", self.path.display());
         }
-        if start_time < 0.0 || end_time <= start_time {
+        if self.start_time < 0.0 || self.end_time <= self.start_time {
             bail!("clip bounds must satisfy 0 <= start < end");
         }
-        if let Some(ts) = trim_start {
+        if let Some(ts) = self.trim_start {
             if ts < 0.0 {
                 bail!("trim_start must be >= 0");
             }
         }
-        if let Some(te) = trim_end {
+        if let Some(te) = self.trim_end {
             if te <= 0.0 {
                 bail!("trim_end must be > 0");
             }
         }
-        if let (Some(ts), Some(te)) = (trim_start, trim_end) {
+        if let (Some(ts), Some(te)) = (self.trim_start, self.trim_end) {
             if te <= ts {
                 bail!("trim_end must be > trim_start");
             }
-            let clip_len = end_time - start_time;
-            if te - ts < clip_len {
+            if te - ts < self.duration() {
                 bail!("trim range shorter than clip duration");
             }
         }
-
-        Ok(Self {
-            path,
-            start_time,
-            end_time,
-            trim_start,
-            trim_end,
-        })
+        Ok(())
     }
 
     pub fn duration(&self) -> f32 {